@@ -0,0 +1,55 @@
+use std::num::NonZeroU32;
+
+use crate::{battle::Campaign, dice::{BattleOutcome, BattleSimulator, DiceClasses}, menu::plugin::starting_team};
+
+/// Plays the starting team against every campaign round with the headless simulator.
+/// A single battle prints its full log instead of the summary.
+pub fn print_balance_report(battles: NonZeroU32, first_seed: u64) {
+  let battles = battles.get();
+  let assets = DiceClasses::read_default().and_then(|classes| {
+    let campaign = Campaign::read_default()?;
+    campaign.validate_classes(&classes)?;
+    let enemies = (1..=campaign.round_count())
+      .map(|round| campaign.round(round).team(&classes))
+      .collect::<Result<Vec<_>, _>>()?;
+    Ok((starting_team(&classes)?, enemies))
+  });
+  let (team, enemies) = match assets {
+    Ok(assets) => assets,
+    Err(error) => {
+      eprintln!("Failed to read the game data: {error}");
+      return;
+    }
+  };
+  for (round, enemy_team) in (1..).zip(enemies) {
+    let mut wins = 0;
+    let mut draws = 0;
+    let mut total_rounds = 0;
+    for seed in (0..battles).map(|i| first_seed.wrapping_add(i as u64)) {
+      let outcome = BattleSimulator::new(team.clone(), enemy_team.clone(), seed).run();
+      if battles == 1 {
+        print_log(&outcome);
+      }
+      match outcome.winner {
+        Some(0) => wins += 1,
+        None => draws += 1,
+        Some(_) => {},
+      }
+      total_rounds += outcome.rounds.last().map_or(0, |log| log.round);
+    }
+    println!(
+      "Round {round}: won {wins}/{battles}, drawn {draws}, {:.2} rounds on average",
+      total_rounds as f32 / battles as f32,
+    );
+  }
+}
+
+fn print_log(outcome: &BattleOutcome) {
+  for round in &outcome.rounds {
+    println!("Round {}", round.round);
+    for entry in &round.entries {
+      println!("  {entry}");
+    }
+  }
+  println!("Winner: {:?}", outcome.winner);
+}
@@ -1,15 +1,150 @@
-use crate::{constants::DICE_COUNT, dice::DiceTemplateBuilder};
+use std::path::Path;
 
-pub struct Challenge(pub [DiceTemplateBuilder; DICE_COUNT]);
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+  constants::{CAMPAIGN_PATH, DEFAULT_LIVES, DEFAULT_MAX_TEAM_SIZE, STARTING_CLASSES},
+  dice::{collect_dice_classes, DiceClasses, DiceTemplateBuilder, FaceId, FacePrototype},
+  loading_screen::AssetStore,
+  states::GameState,
+  utils::ron_asset::{RegisterRonAsset, RonAsset, RonError},
+};
+
+pub struct CampaignPlugin;
+
+impl Plugin for CampaignPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .register_ron_asset::<Campaign>()
+      .add_systems(OnExit(GameState::Loading), insert_campaign.after(collect_dice_classes));
+  }
+}
+
+/// Enemy teams fought through a run, loaded from `assets/main.campaign.ron`
+#[derive(Asset, Resource, TypePath, Deserialize, Clone)]
+pub struct Campaign {
+  /// Most dices a team can have, for the player and for the challenges
+  #[serde(default = "default_max_team_size")]
+  pub max_team_size: usize,
+  /// Battles the player can lose before the run ends
+  #[serde(default = "default_lives")]
+  pub lives: u32,
+  pub rounds: Vec<Challenge>,
+}
+
+fn default_max_team_size() -> usize {
+  DEFAULT_MAX_TEAM_SIZE
+}
+
+fn default_lives() -> u32 {
+  DEFAULT_LIVES
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Challenge {
+  /// Coins earned by beating this round
+  pub reward: u32,
+  pub enemies: Vec<ChallengeDice>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ChallengeDice {
+  pub class: String,
+  pub level: u32,
+  /// Replaces the HP given by the class
+  #[serde(default)]
+  pub hp: Option<u32>,
+  /// Replaced after the class upgrades are applied
+  #[serde(default)]
+  pub faces: Vec<(FaceId, FacePrototype)>,
+}
+
+impl RonAsset for Campaign {
+  const EXTENSIONS: &'static [&'static str] = &["campaign.ron"];
+
+  fn validate(&self) -> Result<(), RonError> {
+    if self.rounds.is_empty() {
+      return Err("Campaign has no rounds".into());
+    }
+    if self.lives == 0 {
+      return Err("Campaign has no lives".into());
+    }
+    if self.max_team_size < STARTING_CLASSES.len() {
+      return Err(format!("Campaign team size {} cannot hold the {} starting dices", self.max_team_size, STARTING_CLASSES.len()).into());
+    }
+    if let Some(round) = self.rounds.iter().position(|round| round.enemies.is_empty()) {
+      return Err(format!("Campaign round {} has no enemies", round + 1).into());
+    }
+    if let Some(round) = self.rounds.iter().position(|round| round.enemies.len() > self.max_team_size) {
+      return Err(format!("Campaign round {} has more than {} enemies", round + 1, self.max_team_size).into());
+    }
+    Ok(())
+  }
+}
+
+impl Campaign {
+  pub fn round_count(&self) -> u32 {
+    self.rounds.len() as u32
+  }
+
+  /// Rounds are numbered from 1 like `ShopRound`
+  pub fn round(&self, round: u32) -> &Challenge {
+    &self.rounds[round as usize - 1]
+  }
+
+  pub fn read_default() -> Result<Self, RonError> {
+    Self::read(&Path::new("assets").join(CAMPAIGN_PATH))
+  }
+
+  /// Rejects enemies of unknown classes or levels, which can only be checked once the classes are loaded
+  pub fn validate_classes(&self, classes: &DiceClasses) -> Result<(), RonError> {
+    for (round, challenge) in self.rounds.iter().enumerate() {
+      for enemy in &challenge.enemies {
+        let Some(class) = classes.get(&enemy.class) else {
+          return Err(format!("Campaign round {} has an enemy of unknown class {}", round + 1, enemy.class).into());
+        };
+        if !(1..=class.max_level()).contains(&enemy.level) {
+          return Err(format!(
+            "Campaign round {} has a {} of level {}, outside of 1 to {}",
+            round + 1, enemy.class, enemy.level, class.max_level(),
+          ).into());
+        }
+      }
+    }
+    Ok(())
+  }
+}
 
 impl Challenge {
-  pub fn new(level: u32) -> Self {
-    Self([
-      DiceTemplateBuilder::berserker(level),
-      DiceTemplateBuilder::paladin(level),
-      DiceTemplateBuilder::cleric(level),
-      DiceTemplateBuilder::mage(level),
-      DiceTemplateBuilder::rogue(level),
-    ])
+  pub fn team(&self, classes: &DiceClasses) -> Result<Vec<DiceTemplateBuilder>, RonError> {
+    self.enemies
+      .iter()
+      .map(|enemy| {
+        let class = classes.get(&enemy.class).ok_or_else(|| format!("Unknown enemy class {}", enemy.class))?;
+        let mut builder = DiceTemplateBuilder::from_class(class, enemy.level);
+        if let Some(hp) = enemy.hp {
+          builder = builder.with_hp(hp);
+        }
+        for &(face_id, face) in &enemy.faces {
+          builder = builder.with_face(face_id, face);
+        }
+        Ok(builder)
+      })
+      .collect()
   }
 }
+
+fn insert_campaign(
+  mut commands: Commands,
+  asset_store: Res<AssetStore>,
+  campaigns: Res<Assets<Campaign>>,
+  classes: Res<DiceClasses>,
+) -> Result {
+  let campaign = campaigns
+    .get(&asset_store.get::<Campaign>(CAMPAIGN_PATH))
+    .ok_or("Campaign is not loaded")?;
+  campaign.validate_classes(&classes)?;
+  commands.insert_resource(campaign.clone());
+  Ok(())
+}
@@ -1,13 +1,15 @@
 use bevy::prelude::*;
 
-use crate::utils::tooltip::toggle_tooltips;
+use crate::{dice::{Dice, SavedStatus, StatusKind, StatusRegistry}, states::GameState, utils::tooltip::toggle_tooltips};
 
 pub struct DebugControlPlugin;
 
 impl Plugin for DebugControlPlugin {
   fn build(&self, app: &mut App) {
     app
-      .add_systems(Update, debug_control);
+      .init_resource::<StatusSnapshot>()
+      // Digits are typed into the seed field of the menu
+      .add_systems(Update, debug_control.run_if(not(in_state(GameState::Menu))));
   }
 }
 
@@ -47,4 +49,51 @@ fn debug_control(
   if keys.just_pressed(KeyCode::KeyT) {
     commands.run_system_cached(toggle_tooltips);
   }
+
+  if keys.just_pressed(KeyCode::KeyI) {
+    commands.run_system_cached(print_statuses);
+  }
+
+  if keys.just_pressed(KeyCode::KeyO) {
+    commands.run_system_cached(restore_statuses);
+  }
+}
+
+/// Statuses of every dice as last printed, put back on the dices by `restore_statuses`
+#[derive(Resource, Default)]
+struct StatusSnapshot(Vec<(Entity, Vec<SavedStatus>)>);
+
+fn print_statuses(
+  registry: Res<StatusRegistry>,
+  dices: Query<(EntityRef, &Dice)>,
+  mut snapshot: ResMut<StatusSnapshot>,
+) {
+  let known = registry.known_statuses().map(|status| status.name).collect::<Vec<_>>();
+  info!("Known statuses: {}", known.join(", "));
+  snapshot.0.clear();
+  for (entity, dice) in dices {
+    let statuses = registry.serialize_statuses_of(&entity);
+    let text = statuses
+      .iter()
+      .map(|status| format!("{}{}", status.name, status.ron))
+      .collect::<Vec<_>>();
+    info!("{:?}: {}", dice.id(), text.join(", "));
+    snapshot.0.push((entity.id(), statuses));
+  }
+}
+
+fn restore_statuses(world: &mut World) {
+  let registry = world.resource::<StatusRegistry>().clone();
+  let snapshot = std::mem::take(&mut world.resource_mut::<StatusSnapshot>().0);
+  for (entity, statuses) in &snapshot {
+    let Ok(mut entity) = world.get_entity_mut(*entity) else { continue };
+    registry.remove_kind(&mut entity, StatusKind::Buff);
+    registry.remove_kind(&mut entity, StatusKind::Debuff);
+    for status in statuses {
+      if let Err(error) = registry.deserialize_status(&mut entity, status) {
+        warn!("Failed to restore {}: {error}", status.name);
+      }
+    }
+  }
+  world.resource_mut::<StatusSnapshot>().0 = snapshot;
 }
@@ -1,11 +1,15 @@
 pub mod plugin;
 
+mod balance;
 mod challenge;
 mod debug_control;
 mod scene;
 mod sequence;
 mod floating_text;
+pub mod replay;
+pub mod rules;
 
-pub use challenge::Challenge;
+pub use balance::print_balance_report;
+pub use challenge::Campaign;
 pub use floating_text::SpawnFloatingText;
-pub use sequence::{StartRound, StartGame, clean_up_game};
+pub use sequence::{StartRound, EndRound, StartGame, BeforeRollDices, clean_up_game};
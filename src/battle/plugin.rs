@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use super::{debug_control::DebugControlPlugin, scene::ScenePlugin, sequence::SequencePlugin, floating_text::FloatingTextPlugin};
+use super::{challenge::CampaignPlugin, debug_control::DebugControlPlugin, scene::ScenePlugin, sequence::SequencePlugin, floating_text::FloatingTextPlugin, replay::ReplayPlugin};
 
 pub struct BattlePlugin;
 
@@ -12,6 +12,8 @@ impl Plugin for BattlePlugin {
         ScenePlugin,
         DebugControlPlugin,
         FloatingTextPlugin,
+        ReplayPlugin,
+        CampaignPlugin,
       ));
   }
 }
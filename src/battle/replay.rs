@@ -0,0 +1,178 @@
+use std::{collections::VecDeque, error::Error};
+
+use bevy::prelude::*;
+use bevy_defer::{AsyncAccess, AsyncWorld};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  constants::REPLAY_PATH,
+  dice::{DiceID, DiceTemplate, DiceTemplateBuilder, Face, Health},
+  manage::plugin::{EnemyTeam, MyTeam},
+  states::GameState,
+};
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_systems(OnEnter(GameState::Battle), start_recording);
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct BattleReplay {
+  pub teams: [Vec<DiceTemplateBuilder>; 2],
+  pub events: Vec<ReplayEvent>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum ReplayEvent {
+  DicesRolled(RolledDices),
+  TargetSelected { dice_id: DiceID, target: Option<DiceID> },
+  StatusApplied { dice_id: DiceID, status: String },
+  Damage { dice_id: DiceID, amount: u32 },
+}
+
+/// Row order and the index of the face each dice landed on
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RolledDices {
+  pub rows: [Vec<DiceID>; 2],
+  pub faces: Vec<(DiceID, usize)>,
+}
+
+impl BattleReplay {
+  pub fn save(&self) -> Result<(), Box<dyn Error>> {
+    let text = ron::ser::to_string_pretty(self, PrettyConfig::default())?;
+    std::fs::write(REPLAY_PATH, text)?;
+    Ok(())
+  }
+
+  pub fn load() -> Result<Self, Box<dyn Error>> {
+    let text = std::fs::read_to_string(REPLAY_PATH)?;
+    Ok(ron::from_str(&text)?)
+  }
+}
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct ReplayRecording(BattleReplay);
+
+/// Decisions of the replayed battle, consumed in the order they were recorded
+#[derive(Resource)]
+pub struct ReplayPlayback {
+  rolls: VecDeque<RolledDices>,
+  targets: VecDeque<Option<DiceID>>,
+}
+
+impl ReplayPlayback {
+  pub fn new(replay: &BattleReplay) -> Self {
+    let mut rolls = VecDeque::new();
+    let mut targets = VecDeque::new();
+    for event in &replay.events {
+      match event {
+        ReplayEvent::DicesRolled(rolled) => rolls.push_back(rolled.clone()),
+        ReplayEvent::TargetSelected { target, .. } => targets.push_back(*target),
+        ReplayEvent::StatusApplied { .. } | ReplayEvent::Damage { .. } => {},
+      }
+    }
+    Self { rolls, targets }
+  }
+
+  pub fn next_roll(&mut self) -> Option<RolledDices> {
+    self.rolls.pop_front()
+  }
+
+  pub fn next_target(&mut self) -> Option<Option<DiceID>> {
+    self.targets.pop_front()
+  }
+}
+
+/// Adds the event to the battle being recorded, nothing is recorded during playback
+pub async fn record(event: ReplayEvent) {
+  let _ = AsyncWorld
+    .resource::<ReplayRecording>()
+    .get_mut(|recording| recording.events.push(event));
+}
+
+/// Returns the next recorded decision if a replay is being played back
+pub async fn replayed<T>(next: impl FnOnce(&mut ReplayPlayback) -> Option<T>) -> Option<T> {
+  AsyncWorld
+    .resource::<ReplayPlayback>()
+    .get_mut(next)
+    .ok()
+    .flatten()
+}
+
+pub fn is_replaying() -> bool {
+  AsyncWorld.resource::<ReplayPlayback>().exists()
+}
+
+fn start_recording(
+  mut commands: Commands,
+  my_team: Single<&Children, With<MyTeam>>,
+  enemy_team: Single<&Children, With<EnemyTeam>>,
+  templates: Query<(&DiceTemplate, &Children, &Health)>,
+  faces: Query<&Face>,
+) {
+  let record_team = |team: &Children| -> Vec<DiceTemplateBuilder> {
+    team
+      .iter()
+      .map(|template| {
+        let (dice_template, template_faces, health) = templates.get(template).unwrap();
+        DiceTemplateBuilder::from_template(dice_template, template_faces, health, &faces)
+      })
+      .collect()
+  };
+
+  commands.insert_resource(ReplayRecording(BattleReplay {
+    teams: [record_team(&my_team), record_team(&enemy_team)],
+    events: Vec::new(),
+  }));
+}
+
+pub fn save_replay(
+  mut commands: Commands,
+  recording: Option<Res<ReplayRecording>>,
+) {
+  let Some(recording) = recording else { return };
+  if let Err(error) = recording.save() {
+    warn!("Failed to save replay: {error}");
+  }
+  commands.remove_resource::<ReplayRecording>();
+}
+
+pub fn start_replay(
+  mut commands: Commands,
+  mut images: ResMut<Assets<Image>>,
+  mut game_state: ResMut<NextState<GameState>>,
+) {
+  let replay = match BattleReplay::load() {
+    Ok(replay) => replay,
+    Err(error) => {
+      warn!("Failed to load replay: {error}");
+      return;
+    }
+  };
+
+  let [my_team, enemy_team] = &replay.teams;
+  commands.spawn((
+    Name::new("My team"),
+    MyTeam,
+  )).with_children(|commands| {
+    for builder in my_team {
+      builder.spawn(commands, &mut images);
+    }
+  });
+  commands.spawn((
+    Name::new("Enemy team"),
+    EnemyTeam,
+  )).with_children(|commands| {
+    for builder in enemy_team {
+      builder.spawn(commands, &mut images);
+    }
+  });
+
+  commands.insert_resource(ReplayPlayback::new(&replay));
+  game_state.set(GameState::Replay);
+}
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, seq::SliceRandom};
+
+use crate::dice::{
+  status::{Burning, Double, Frozen, Immune, Poison, Regeneration, Shield, Status, StatusKind},
+  Action, DiceID, FaceBackground, FacePrototype, Health,
+};
+
+// How faces, damage, heals and statuses change the dices, shared by the battle listeners and the `BattleSimulator`
+
+/// Lets the shield take the damage first, returns what is left of the shield and of the damage
+pub fn absorb_damage(shield: Shield, damage: u32) -> (Option<Shield>, u32) {
+  let absorbed = shield.amount.min(damage);
+  let shield = Shield { amount: shield.amount - absorbed };
+  ((shield.amount > 0).then_some(shield), damage - absorbed)
+}
+
+/// Returns true if the damage killed the dice
+pub fn take_damage(health: &mut Health, damage: u32) -> bool {
+  health.current = health.current.saturating_sub(damage);
+  health.current == 0
+}
+
+pub fn heal(health: &mut Health, amount: u32) {
+  health.current = (health.current + amount).min(health.max);
+}
+
+/// The status of a dice once a new one of the same type is applied on top of it
+pub fn stack_status<S: Status>(current: Option<S>, status: S) -> S {
+  match current {
+    Some(current) => current.combine(status),
+    None => status,
+  }
+}
+
+/// Counts the status down after it triggered, `None` once it wore off
+pub fn tick_status<S: Status>(mut status: S) -> Option<S> {
+  (!status.update()).then_some(status)
+}
+
+/// Stacks the status on the dice, returns false if the dice is `Immune` to it
+pub fn apply_status<S: Status>(entity: &mut EntityWorldMut, status: S) -> bool {
+  if entity.contains::<Immune<S>>() {
+    return false
+  }
+  let current = entity.take::<S>();
+  entity.insert(stack_status(current, status));
+  true
+}
+
+/// What a status does to its own dice when it triggers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusEffect {
+  Damage(u32),
+  Heal(u32),
+}
+
+/// A status about to be applied, whatever its type
+#[derive(Clone)]
+pub struct NewStatus {
+  pub name: &'static str,
+  pub color: Color,
+  apply: Arc<dyn Fn(&mut EntityWorldMut) -> bool + Send + Sync>,
+}
+
+impl NewStatus {
+  pub fn new<S: Status>(status: S) -> Self {
+    Self {
+      name: S::name(),
+      color: S::STATUS_COLOR,
+      apply: Arc::new(move |entity| apply_status(entity, status)),
+    }
+  }
+
+  /// See `apply_status`
+  pub fn apply(&self, entity: &mut EntityWorldMut) -> bool {
+    (self.apply)(entity)
+  }
+}
+
+/// Dice a face picks to resolve on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActionTarget {
+  Myself,
+  Ally,
+  Enemy,
+}
+
+/// What a face does to its target
+#[derive(Clone)]
+pub enum ActionEffect {
+  Damage(u32),
+  Status(NewStatus),
+  RemoveStatuses(StatusKind),
+}
+
+/// How many times the face resolves
+pub fn repeats(face: FacePrototype) -> u32 {
+  if face.background == FaceBackground::Double { 2 } else { 1 }
+}
+
+/// Target and effect of an action given its pips, once `GetPips` listeners changed them.
+/// `None` when the action does nothing, then no target is picked.
+pub fn action_rule(action: Action, pips: Option<u32>) -> Option<(ActionTarget, ActionEffect)> {
+  // Attacks pick their target even without pips, the other actions need some
+  let some_pips = pips.filter(|&pips| pips > 0);
+  let rule = match action {
+    Action::Empty | Action::Fiery => return None,
+    Action::Attack => (ActionTarget::Enemy, ActionEffect::Damage(pips?)),
+    Action::Defend => (ActionTarget::Myself, ActionEffect::Status(NewStatus::new(Double))),
+    Action::Regenerate => (ActionTarget::Ally, ActionEffect::Status(NewStatus::new(Regeneration::new(some_pips?)))),
+    Action::Fire => (ActionTarget::Enemy, ActionEffect::Status(NewStatus::new(Burning { intensity: some_pips? }))),
+    Action::Block => (ActionTarget::Myself, ActionEffect::Status(NewStatus::new(Shield { amount: some_pips? }))),
+    Action::Poison => (ActionTarget::Enemy, ActionEffect::Status(NewStatus::new(Poison { stacks: some_pips? }))),
+    Action::Freeze => (ActionTarget::Enemy, ActionEffect::Status(NewStatus::new(Frozen { turns: 1 }))),
+    Action::Cleanse => (ActionTarget::Ally, ActionEffect::RemoveStatuses(StatusKind::Debuff)),
+  };
+  Some(rule)
+}
+
+/// Picks a random candidate, a cruel face only picks among the ones with the lowest health
+pub fn choose_target(mut candidates: Vec<(DiceID, u32)>, cruel: bool, rng: &mut StdRng) -> Option<DiceID> {
+  if cruel {
+    let lowest = candidates.iter().map(|&(_, health)| health).min();
+    candidates.retain(|&(_, health)| Some(health) == lowest);
+  }
+  candidates.choose(rng).map(|&(dice_id, _)| dice_id)
+}
@@ -1,7 +1,7 @@
 use avian3d::prelude::*;
 use bevy::prelude::*;
 
-use crate::{constants::{BASE_SCALE, GRAVITY_ACCELERATION, HEIGHT, WALL_SIZE, WIDTH}, loading_screen::AssetStore, states::GameState};
+use crate::{constants::{BASE_SCALE, GRAVITY_ACCELERATION, HEIGHT, WALL_SIZE, WIDTH}, loading_screen::AssetStore, states::InBattle};
 
 pub struct ScenePlugin;
 
@@ -9,8 +9,8 @@ impl Plugin for ScenePlugin {
   fn build(&self, app: &mut App) {
     app
       .add_plugins(PhysicsPlugins::default())
-      .add_systems(OnEnter(GameState::Battle), spawn_battle_scene)
-      .add_systems(OnExit(GameState::Battle), despawn_battle_scene)
+      .add_systems(OnEnter(InBattle), spawn_battle_scene)
+      .add_systems(OnExit(InBattle), despawn_battle_scene)
       .insert_resource(Gravity(Vec3::NEG_Z * GRAVITY_ACCELERATION));
   }
 }
@@ -1,10 +1,14 @@
 use bevy::prelude::*;
 use bevy_defer::{AccessError, AsyncAccess, AsyncCommandsExtension, AsyncWorld};
 
+use crate::battle::Campaign;
+use crate::battle::replay::{is_replaying, save_replay, ReplayPlayback};
 use crate::camera::SwapBattleCamera;
 use crate::constants::DICE_SIZE;
-use crate::manage::plugin::{Coins, EnemyTeam, MyTeam, ShopRound};
-use crate::states::GameState;
+use crate::manage::economy::{collect_income, Losses};
+use crate::manage::plugin::{EnemyTeam, MyTeam, Shop, ShopRound};
+use crate::manage::save::RunSave;
+use crate::states::{GameState, InBattle};
 use crate::dice::{move_dices_to_rows, resolve_dices, roll_dices, Dice};
 use crate::utils::*;
 
@@ -13,11 +17,12 @@ pub struct SequencePlugin;
 impl Plugin for SequencePlugin {
   fn build(&self, app: &mut App) {
     app
-      .add_systems(OnEnter(GameState::Battle), |mut commands: Commands| {
+      .add_systems(OnEnter(InBattle), |mut commands: Commands| {
         commands.spawn_task(flow);
       })
       .add_event_and_listen::<StartGame>()
       .add_event_and_listen::<StartRound>()
+      .add_event_and_listen::<EndRound>()
       .add_event_and_listen::<BeforeRollDices>()
       .add_event_and_listen::<BeforeResolveDices>();
   }
@@ -29,12 +34,21 @@ pub struct StartGame;
 #[derive(Event, Clone, Copy, Debug)]
 pub struct StartRound {
   #[allow(unused)]
-  round: u32,
+  pub round: u32,
 }
 
 
 #[derive(Event, Clone, Copy, Debug)]
-pub struct BeforeRollDices;
+pub struct EndRound {
+  #[allow(unused)]
+  pub round: u32,
+}
+
+/// Triggered after every `StartRound` listener, so statuses already expired for the round
+#[derive(Event, Clone, Copy, Debug)]
+pub struct BeforeRollDices {
+  pub round: u32,
+}
 
 #[derive(Event, Clone, Copy, Debug)]
 pub struct BeforeResolveDices;
@@ -48,23 +62,44 @@ async fn flow() -> Result<(), AccessError> {
   loop {
     AsyncWorld.trigger_event(StartRound { round: current_round }.wrap()).await?;
 
-    AsyncWorld.trigger_event(BeforeRollDices.wrap()).await?;
+    AsyncWorld.trigger_event(BeforeRollDices { round: current_round }.wrap()).await?;
     roll_dices().await?;
 
     AsyncWorld.trigger_event(BeforeResolveDices.wrap()).await?;
     resolve_dices().await?;
 
+    AsyncWorld.trigger_event(EndRound { round: current_round }.wrap()).await?;
+
     if let Some(won) = done().await? {
+      if is_replaying() {
+        AsyncWorld.resource::<ReplayPlayback>().remove();
+        end_game(if won { "REPLAY: WON!" } else { "REPLAY: LOST" }).await?;
+        return Ok(())
+      }
+
+      AsyncWorld.run_system_cached(save_replay)?;
       if won {
-        if AsyncWorld.resource::<ShopRound>().get(|round| **round)? == 4 {
+        let round = AsyncWorld.resource::<ShopRound>().get(|round| **round)?;
+        let round_count = AsyncWorld.resource::<Campaign>().get(|campaign| campaign.round_count())?;
+        if round == round_count {
+          RunSave::delete();
           end_game("WON!").await?;
         } else {
+          AsyncWorld.run_system_cached_with(collect_income, true)?;
           AsyncWorld.resource::<ShopRound>().get_mut(|round| **round += 1)?;
-          AsyncWorld.resource::<Coins>().get_mut(|coins| **coins += 5)?;
           AsyncWorld.set_state(GameState::Manage)?;
         }
       } else {
-        end_game("LOST").await?;
+        let losses = AsyncWorld.resource::<Losses>().get_mut(|losses| { **losses += 1; **losses })?;
+        let lives = AsyncWorld.resource::<Campaign>().get(|campaign| campaign.lives)?;
+        if losses >= lives {
+          RunSave::delete();
+          end_game("LOST").await?;
+        } else {
+          // The same round is fought again
+          AsyncWorld.run_system_cached_with(collect_income, false)?;
+          AsyncWorld.set_state(GameState::Manage)?;
+        }
       }
       return Ok(())
     }
@@ -125,6 +160,7 @@ async fn end_game(ending_text: &'static str) -> Result<(), AccessError> {
 pub fn clean_up_game(
   my_team: Single<Entity, With<MyTeam>>,
   enemy_team: Single<Entity, With<EnemyTeam>>,
+  shop: Option<Single<Entity, With<Shop>>>,
   dices: Query<Entity, With<Dice>>,
   mut commands: Commands,
 ) {
@@ -133,4 +169,8 @@ pub fn clean_up_game(
   }
   commands.entity(*my_team).despawn();
   commands.entity(*enemy_team).despawn();
+  // Replays have no shop
+  if let Some(shop) = shop {
+    commands.entity(*shop).despawn();
+  }
 }
@@ -4,7 +4,7 @@ use bevy::animation::{animated_field, AnimationTarget, AnimationTargetId, Repeat
 use crate::constants::CAMERA_SWAP_TIME;
 use crate::{
   constants::{DEFAULT_CAMERA_DISTANCE, MAX_CAMERA_DISTANCE, HEIGHT},
-  states::GameState
+  states::InBattle
 };
 
 #[derive(Component)]
@@ -22,9 +22,9 @@ impl Plugin for BattleCameraPlugin {
   fn build(&self, app: &mut App) {
     app
       .add_event::<SwapBattleCamera>()
-      .add_systems(OnEnter(GameState::Battle), spawn_battle_camera)
-      .add_systems(OnExit(GameState::Battle), despawn_battle_camera)
-      .add_systems(Update, update_camera_state.run_if(in_state(GameState::Battle)))
+      .add_systems(OnEnter(InBattle), spawn_battle_camera)
+      .add_systems(OnExit(InBattle), despawn_battle_camera)
+      .add_systems(Update, update_camera_state.run_if(in_state(InBattle)))
       .add_systems(Update, swap_camera.run_if(on_event::<SwapBattleCamera>))
       .init_resource::<LocalResources>();
   }
@@ -14,6 +14,22 @@ pub const GRID_FACE_SIZE: f32 = 0.5 * DICE_SIZE;
 
 pub const REFRESH_PRICE: u32 = 1;
 
+pub const STARTING_COINS: u32 = 5;
+/// Level 1 dices of these classes make the team of a new run
+pub const STARTING_CLASSES: [&str; 5] = ["berserker", "paladin", "mage", "cleric", "rogue"];
+/// Saving this many coins earns one coin of interest after a battle
+pub const INTEREST_STEP: u32 = 5;
+pub const MAX_INTEREST: u32 = 3;
+/// Coins earned after a lost battle
+pub const CONSOLATION_INCOME: u32 = 3;
+pub const DEFAULT_LIVES: u32 = 3;
+
+pub const REPLAY_PATH: &str = "last_battle.replay.ron";
+pub const SAVE_PATH: &str = "run.save.ron";
+
+pub const DICE_CLASSES_FOLDER: &str = "classes";
+pub const CAMPAIGN_PATH: &str = "main.campaign.ron";
+
 pub mod ui {
   use bevy::ui::Val;
   use crate::constants::BASE_SCALE;
@@ -22,6 +38,9 @@ pub mod ui {
   pub const REFRESH_BUTTON_SIZE: Val = Val::Px(BASE_SCALE * 5.0);
   pub const ROUND_NUMBER_SIZE: f32 = BASE_SCALE * 2.5;
   pub const COINS_NUMBER_SIZE: f32 = BASE_SCALE * 2.5;
+
+  pub const SYNERGY_HIGHLIGHT_DURATION: f32 = 1.0;
+  pub const TILE_FRAME_WIDTH: f32 = BASE_SCALE * 0.4;
 }
 
 pub mod dice_texture {
@@ -53,6 +72,9 @@ pub mod dice_info_bar {
 
   pub const STATUS_ICON_SIZE: Vec2 = Vec2::splat(0.125 * DICE_SIZE);
   pub const STATUS_TEXT_SIZE: f32 = 0.125 * DICE_SIZE;
+  pub const STATUS_BORDER: f32 = 0.01 * DICE_SIZE;
+  pub const STATUS_PULSE_DURATION: f32 = 0.4;
+  pub const STATUS_PULSE_SCALE: f32 = 0.5;
 
   pub const HEALTH_BAR_WIDTH: f32 = 0.5 * DICE_SIZE;
   pub const HEALTH_BAR_HEIGHT: f32 = 0.125 * DICE_SIZE;
@@ -77,8 +99,12 @@ pub const CAMERA_SWAP_TIME: f32 = 0.5;
 // Dice are assumed to be around the size of 1 centimeter
 pub const GRAVITY_ACCELERATION: f32 = 9.81 * DICE_SIZE * 10.0;
 
-pub const DICE_COUNT: usize = 5;
+pub const DEFAULT_MAX_TEAM_SIZE: usize = 5;
 pub const SHOP_ITEMS_COUNT: usize = 4;
+/// Chance for a shop spot to offer a whole dice instead of a tile
+pub const DICE_OFFER_CHANCE: f64 = 0.2;
+pub const ITEM_OFFER_CHANCE: f64 = 0.2;
+pub const STICKER_OFFER_CHANCE: f64 = 0.1;
 
 pub const DICE_FACES_LAYER: RenderLayers = RenderLayers::layer(1);
 pub const TOOLTIP_LAYER: RenderLayers = RenderLayers::layer(2);
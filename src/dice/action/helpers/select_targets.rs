@@ -1,81 +1,61 @@
-use bevy::ecs::query::QueryData;
-use bevy_defer::{AccessError, AsyncWorld};
+use std::future::Future;
 
+use bevy_defer::{AccessError, AsyncAccess, AsyncWorld};
+
+use crate::battle::replay::{record, replayed, ReplayEvent, ReplayPlayback};
 use crate::dice::action::ResolutionContext;
 use crate::dice::background::FaceBackground;
 use crate::dice::dice_instance::Health;
+use crate::battle::rules;
 use crate::dice::{Dice, DiceID};
+use crate::utils::rng::GameRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
 
 
 pub async fn select_enemy(context: ResolutionContext) -> Result<Option<DiceID>, AccessError> {
-  let filter = |dice: &Dice| { dice.id().team_id != context.dice_id.team_id };
-
-  if context.face.background == FaceBackground::Cruel {
-    return select_random_best::<i32, &Dice, &Health>(
-      filter,
-      |health: &Health| { -(health.current as i32) },
-    ).await;
-  }
-
-  select_random(filter).await
+  recorded_target(context.dice_id, choose_enemy(context)).await
 }
 
 pub async fn select_ally(context: ResolutionContext) -> Result<Option<DiceID>, AccessError> {
-  let filter = |dice: &Dice| {
-    dice.id().team_id == context.dice_id.team_id
-    && dice.id() != context.dice_id
-  };
+  recorded_target(context.dice_id, choose_ally(context)).await
+}
 
-  if context.face.background == FaceBackground::Cruel {
-    return select_random_best::<i32, &Dice, &Health>(
-      filter,
-      |health: &Health| { -(health.current as i32) },
-    ).await;
+async fn recorded_target(
+  dice_id: DiceID,
+  choose: impl Future<Output = Result<Option<DiceID>, AccessError>>,
+) -> Result<Option<DiceID>, AccessError> {
+  if let Some(target) = replayed(ReplayPlayback::next_target).await {
+    return Ok(target);
   }
-
-  select_random(filter).await
+  let target = choose.await?;
+  record(ReplayEvent::TargetSelected { dice_id, target }).await;
+  Ok(target)
 }
 
-pub async fn select_random(filter: impl Fn(&Dice) -> bool) -> Result<Option<DiceID>, AccessError> {
-  let mut filtered_dices = Vec::new();
-  AsyncWorld
-    .query::<&Dice>()
-    .for_each(|dice| {
-      if filter(dice) {
-        filtered_dices.push(dice.id());
-      }
-    });
-  Ok(filtered_dices.select_random())
+async fn choose_enemy(context: ResolutionContext) -> Result<Option<DiceID>, AccessError> {
+  choose_target(context, |dice_id| dice_id.team_id != context.dice_id.team_id).await
 }
 
-pub async fn select_random_best<S, FilterData: QueryData + 'static, ScoreData: QueryData + 'static>(
-  filter: impl Fn(FilterData::Item<'_>) -> bool,
-  score: impl Fn(ScoreData::Item<'_>) -> S
-) -> Result<Option<DiceID>, AccessError>
-where S: Ord + Eq + Copy {
-  let mut best_dices = Vec::new();
-  let mut best_score = None;
+async fn choose_ally(context: ResolutionContext) -> Result<Option<DiceID>, AccessError> {
+  choose_target(context, |dice_id| {
+    dice_id.team_id == context.dice_id.team_id
+    && dice_id != context.dice_id
+  }).await
+}
 
+async fn choose_target(context: ResolutionContext, filter: impl Fn(DiceID) -> bool) -> Result<Option<DiceID>, AccessError> {
+  let mut candidates = Vec::new();
   AsyncWorld
-    .query::<(FilterData, ScoreData, &Dice)>()
-    .for_each(|data| {
-      let (filter_data, score_data, dice) = data;
-      if filter(filter_data) {
-        let current_score = score(score_data);
-        if best_score.is_none() || current_score > best_score.unwrap() {
-          best_score = Some(current_score);
-          best_dices.clear();
-          best_dices.push(dice.id());
-        }
-        if best_score == Some(current_score) {
-          best_dices.push(dice.id());
-        }
+    .query::<(&Dice, &Health)>()
+    .for_each(|(dice, health)| {
+      if filter(dice.id()) {
+        candidates.push((dice.id(), health.current));
       }
     });
-
-  Ok(best_dices.select_random())
+  let cruel = context.face.background == FaceBackground::Cruel;
+  AsyncWorld
+    .resource::<GameRng>()
+    .get_mut(|rng| rules::choose_target(candidates, cruel, rng.targeting()))
 }
 
 #[allow(dead_code)]
@@ -98,20 +78,21 @@ where S: Ord + Eq + Copy {
       }
     });
 
-  Ok(best_dices.select_random())
+  best_dices.select_random().await
 }
 
 trait SelectRandom {
-  fn select_random(&self) -> Option<DiceID>;
+  async fn select_random(&self) -> Result<Option<DiceID>, AccessError>;
 }
 
 impl SelectRandom for Vec<DiceID> {
-  fn select_random(&self) -> Option<DiceID> {
+  async fn select_random(&self) -> Result<Option<DiceID>, AccessError> {
     if self.is_empty() {
-      None
+      Ok(None)
     } else {
-      let mut rng = thread_rng();
-      self.choose(&mut rng).copied()
+      AsyncWorld
+        .resource::<GameRng>()
+        .get_mut(|rng| self.choose(rng.targeting()).copied())
     }
   }
 }
@@ -2,63 +2,159 @@ use bevy::prelude::*;
 use bevy_defer::{fetch, AccessError, AsyncAccess, AsyncWorld};
 
 use crate::dice::dice_instance::Health;
-use crate::dice::status::Status;
-use crate::dice::{animation::get_dice_entity, events::DiceDied, DiceID};
+use crate::dice::status::{Shield, Status, StatusInfo, StatusKind, StatusRegistry};
+use crate::battle::rules::{self, take_damage, NewStatus, StatusEffect};
+use crate::dice::{animation::get_dice_entity, events::{AfterDamage, BeforeDamage, BeforeHeal, DiceDied}, DiceID};
 use crate::utils::*;
 use crate::battle::SpawnFloatingText;
+use crate::battle::replay::{record, ReplayEvent};
 
 pub async fn damage(
+  source: Option<DiceID>,
   dice_id: DiceID,
   damage: u32,
   color: Color,
 ) -> Result<(), AccessError> {
+  let before_damage = BeforeDamage::wrap(BeforeDamage { source, target: dice_id, amount: damage, color, cancelled: false });
+  AsyncWorld.trigger_event(before_damage.clone()).await?;
+  let BeforeDamage { target: dice_id, amount: damage, color, cancelled, .. } = before_damage.get();
+  if cancelled || damage == 0 {
+    return Ok(())
+  }
+
   let mut died = false;
   let entity = get_dice_entity(dice_id).await?;
   let position = fetch!(entity, Transform).get(|t| t.translation)?;
+
+  let damage = absorb_damage(entity, damage, position).await?;
+  if damage == 0 {
+    return Ok(())
+  }
+
+  record(ReplayEvent::Damage { dice_id, amount: damage }).await;
   AsyncWorld.send_event(SpawnFloatingText::new(format!("-{damage}"), position).with_color(color))?;
-  fetch!(entity, Health).get_mut(|Health { current, .. }| {
-    let new_hp = current.saturating_sub(damage);
-    *current = new_hp;
-    if new_hp == 0 {
-      died = true;
-    }
-  })?;
+  fetch!(entity, Health).get_mut(|health| died = take_damage(health, damage))?;
+  AsyncWorld.trigger_event(AfterDamage::wrap(AfterDamage { source, target: dice_id, amount: damage })).await?;
   if died {
     AsyncWorld.trigger_event(DiceDied::wrap(DiceDied { dice_id })).await?;
   }
   Ok(())
 }
 
+/// Lets the shield of the dice take the damage first, returns what is left for the health
+async fn absorb_damage(
+  entity: Entity,
+  damage: u32,
+  position: Vec3,
+) -> Result<u32, AccessError> {
+  let Ok(shield) = fetch!(entity, Shield).get(|shield| *shield) else {
+    return Ok(damage)
+  };
+  let (shield_left, damage_left) = rules::absorb_damage(shield, damage);
+  match shield_left {
+    Some(shield_left) => fetch!(entity, Shield).get_mut(|shield| *shield = shield_left)?,
+    None => { AsyncWorld.entity(entity).remove::<Shield>()?; },
+  }
+  AsyncWorld.send_event(
+    SpawnFloatingText::new(format!("({})", damage - damage_left), position)
+      .with_color(Shield::STATUS_COLOR)
+  )?;
+  Ok(damage_left)
+}
+
 pub async fn heal(
+  source: Option<DiceID>,
   dice_id: DiceID,
   heal_amount: u32,
 ) -> Result<(), AccessError> {
+  let before_heal = BeforeHeal::wrap(BeforeHeal { source, target: dice_id, amount: heal_amount, cancelled: false });
+  AsyncWorld.trigger_event(before_heal.clone()).await?;
+  let BeforeHeal { target: dice_id, amount: heal_amount, cancelled, .. } = before_heal.get();
+  if cancelled || heal_amount == 0 {
+    return Ok(())
+  }
+
   let entity = get_dice_entity(dice_id).await?;
   let position = fetch!(entity, Transform).get(|t| t.translation)?;
   AsyncWorld.send_event(
     SpawnFloatingText::new(format!("+{heal_amount}"), position)
       .with_color(Color::linear_rgb(0.0, 1.0, 0.0))
   )?;
-  fetch!(entity, Health).get_mut(|Health { max, current}| {
-    let new_hp = (*current + heal_amount).min(*max);
-    *current = new_hp;
+  fetch!(entity, Health).get_mut(|health| rules::heal(health, heal_amount))?;
+  Ok(())
+}
+
+pub async fn apply_status(
+  dice_id: DiceID,
+  status: NewStatus,
+) -> Result<(), AccessError> {
+  let entity = get_dice_entity(dice_id).await?;
+  let applied = AsyncWorld.run(|world| {
+    world
+      .get_entity_mut(entity)
+      .map(|mut entity| status.apply(&mut entity))
+      .map_err(|_| AccessError::EntityNotFound(entity))
   })?;
+  if !applied {
+    let position = fetch!(entity, Transform).get(|t| t.translation)?;
+    AsyncWorld.send_event(SpawnFloatingText::new("Immune".to_string(), position).with_color(status.color))?;
+    return Ok(())
+  }
+  record(ReplayEvent::StatusApplied { dice_id, status: status.name.to_string() }).await;
   Ok(())
 }
 
-pub async fn apply_status<S: Status>(
+/// Applies what a status does to its dice when it triggers
+pub async fn resolve_status_effect(
   dice_id: DiceID,
-  status: S,
+  effect: StatusEffect,
+  color: Color,
 ) -> Result<(), AccessError> {
+  match effect {
+    StatusEffect::Damage(amount) => damage(None, dice_id, amount, color).await?,
+    StatusEffect::Heal(amount) => heal(None, dice_id, amount).await?,
+  }
+  AsyncWorld.sleep(0.5).await;
+  Ok(())
+}
+
+pub async fn statuses(
+  dice_id: DiceID,
+) -> Result<Vec<StatusInfo>, AccessError> {
   let entity = get_dice_entity(dice_id).await?;
-  let new_status = if let Ok(current_status) = fetch!(entity, S).get(|status| *status) {
-    AsyncWorld.entity(entity).remove::<S>()?;
-    current_status.combine(status)
-  } else {
-    status
-  };
-  AsyncWorld
-    .entity(entity)
-    .insert(new_status)?;
+  AsyncWorld.run(|world| {
+    let registry = world.resource::<StatusRegistry>();
+    world
+      .get_entity(entity)
+      .map(|entity| registry.statuses_of(&entity))
+      .map_err(|_| AccessError::EntityNotFound(entity))
+  })
+}
+
+/// Removes every status of the kind, showing the name of each removed status
+pub async fn remove_statuses(
+  dice_id: DiceID,
+  kind: StatusKind,
+) -> Result<(), AccessError> {
+  let removed = statuses(dice_id)
+    .await?
+    .into_iter()
+    .filter(|status| status.kind == kind)
+    .collect::<Vec<_>>();
+  if removed.is_empty() {
+    return Ok(())
+  }
+
+  let entity = get_dice_entity(dice_id).await?;
+  let position = fetch!(entity, Transform).get(|t| t.translation)?;
+  for status in removed {
+    AsyncWorld.send_event(SpawnFloatingText::new(format!("-{}", status.name), position))?;
+  }
+  AsyncWorld.run(|world| {
+    let registry = world.resource::<StatusRegistry>().clone();
+    if let Ok(mut entity) = world.get_entity_mut(entity) {
+      registry.remove_kind(&mut entity, kind);
+    }
+  });
   Ok(())
 }
@@ -1,21 +1,16 @@
 use bevy::prelude::*;
 use bevy_defer::{AccessError, AsyncWorld};
-use crate::{dice::{background::FaceBackground, FacePrototype}, utils::*};
+use futures_::future::{join, join3};
+use serde::{Deserialize, Serialize};
+use crate::{battle::rules::{self, ActionEffect, ActionTarget}, dice::{animation::spin_dice, FacePrototype}, utils::*};
 
 use super::DiceID;
 
-mod attack;
-mod fire;
-mod double;
-mod regenerate;
-
 mod helpers;
 pub mod interaction;
 
-use attack::attack;
-use fire::fire;
-use double::double;
-use regenerate::regenerate;
+use helpers::*;
+use interaction::dice::{apply_status, damage, remove_statuses};
 
 pub struct DiceActionPlugin;
 
@@ -26,7 +21,7 @@ impl Plugin for DiceActionPlugin {
   }
 }
 
-#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Action {
   #[default]
   Empty,
@@ -35,6 +30,10 @@ pub enum Action {
   Regenerate,
   Fire,
   Fiery,
+  Block,
+  Poison,
+  Freeze,
+  Cleanse,
 }
 
 impl Action {
@@ -46,6 +45,10 @@ impl Action {
       Action::Regenerate => "Apply Regeneration status to ally",
       Action::Fire => "Apply Burning status to opponent",
       Action::Fiery => "Contribute to Fiery synergy",
+      Action::Block => "Apply Shield status to self",
+      Action::Poison => "Apply Poison status to opponent",
+      Action::Freeze => "Apply Frozen status to opponent",
+      Action::Cleanse => "Remove all debuffs from ally",
     }
   }
 }
@@ -59,6 +62,10 @@ impl From<Action> for &'static str {
       Action::Regenerate => "actions/heart.png",
       Action::Fire => "actions/fire.png",
       Action::Fiery => "actions/potion_red.png",
+      Action::Block => "actions/shield.png",
+      Action::Poison => "actions/drop_poison.png",
+      Action::Freeze => "actions/frost.png",
+      Action::Cleanse => "actions/band_aid.png",
     }
   }
 }
@@ -75,25 +82,46 @@ pub struct ResolutionContext {
   pub dice_id: DiceID,
 }
 
+/// Resolves the face following `rules::action_rule`, spinning the dice and its target meanwhile
 pub async fn resolve(
   context: ResolutionContext,
 ) -> Result<(), AccessError> {
   let ResolutionContext { face, dice_id } = context;
-  let FacePrototype { action, pips, background } = face;
-  let repeat = if background == FaceBackground::Double { 2 } else { 1 };
-  for _ in 0..repeat {
-    match action {
-      Action::Empty => Ok(()),
-      Action::Attack => attack(get_pips(dice_id, pips.unwrap()).await?, context).await,
-      Action::Defend => double(context).await,
-      Action::Regenerate => regenerate(get_pips(dice_id, pips.unwrap()).await?, context).await,
-      Action::Fire => fire(get_pips(dice_id, pips.unwrap()).await?, context).await,
-      Action::Fiery => Ok(()),
-    }?
+  for _ in 0..rules::repeats(face) {
+    let pips = match face.pips {
+      Some(pips) => Some(get_pips(dice_id, pips).await?),
+      None => None,
+    };
+    let Some((target, effect)) = rules::action_rule(face.action, pips) else { continue };
+    let target_id = match target {
+      ActionTarget::Myself => Some(dice_id),
+      ActionTarget::Ally => select_ally(context).await?,
+      ActionTarget::Enemy => select_enemy(context).await?,
+    };
+    let Some(target_id) = target_id else { continue };
+
+    let resolution = delayed(0.25, resolve_effect(dice_id, target_id, effect));
+    if target_id == dice_id {
+      join(resolution, spin_dice(dice_id, 0.5)).await.try_all()?;
+    } else {
+      join3(resolution, spin_dice(dice_id, 0.5), spin_dice(target_id, 0.5)).await.try_all()?;
+    }
   }
   Ok(())
 }
 
+async fn resolve_effect(
+  dice_id: DiceID,
+  target_id: DiceID,
+  effect: ActionEffect,
+) -> Result<(), AccessError> {
+  match effect {
+    ActionEffect::Damage(amount) => damage(Some(dice_id), target_id, amount, Color::BLACK).await,
+    ActionEffect::Status(status) => apply_status(target_id, status).await,
+    ActionEffect::RemoveStatuses(kind) => remove_statuses(target_id, kind).await,
+  }
+}
+
 async fn get_pips(
   dice_id: DiceID,
   pips: u32
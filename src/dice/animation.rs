@@ -4,8 +4,8 @@ use bevy::prelude::*;
 use bevy_defer::{fetch, AccessError, AsyncAccess, AsyncWorld};
 use futures_::future::join_all;
 
-use crate::constants::{ANGULAR_SPEED, DICE_SIZE, FACE_NORMALS, HEIGHT, LINEAR_SPEED};
-use super::dice_instance::DiceEntityMap;
+use crate::constants::{ANGULAR_SPEED, DICE_SIZE, FACE_NORMALS, HEIGHT, LINEAR_SPEED, WIDTH};
+use super::dice_instance::{DiceEntityMap, Rows};
 use super::{Dice, DiceID};
 use super::roll::get_face_id;
 
@@ -39,17 +39,22 @@ pub async fn move_dice_to_row(
 ) -> Result<(), AccessError> {
   let entity = get_dice_entity(dice_id).await?;
   let row_position = fetch!(entity, Dice).get(|dice| { dice.row_position() })?;
-  let target_x = compute_target_x(row_position);
+  let row_length = AsyncWorld.resource::<Rows>().get(|rows| {
+    if dice_id.team_id == 0 { rows.team1.len() } else { rows.team2.len() }
+  })?;
+  // Rows are only known after the first roll
+  let target_x = compute_target_x(row_position, row_length.max(row_position + 1));
   let target_y = if dice_id.team_id == 0 { -HEIGHT * 2.0 / 5.0 } else { HEIGHT * 2.0 / 5.0 };
   let target_position = Vec3::new(target_x, target_y, DICE_SIZE / 2.0);
   move_dice(dice_id, target_position).await?;
   Ok(())
 }
 
-fn compute_target_x(row_position: usize) -> f32 {
-  let row_start = -DICE_SIZE * 4.0;
-  
-  row_start + (DICE_SIZE * 2.0 * row_position as f32)
+/// Centers the row on the table, tightening it when the team is too large for the usual spacing
+fn compute_target_x(row_position: usize, row_length: usize) -> f32 {
+  let spacing = (DICE_SIZE * 2.0).min((WIDTH - DICE_SIZE * 2.0) / (row_length.max(2) - 1) as f32);
+
+  (row_position as f32 - (row_length - 1) as f32 / 2.0) * spacing
 }
 
 pub async fn move_dice(
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::{constants::dice_texture::INNER_SIZE};
 
@@ -9,7 +10,7 @@ impl Plugin for FaceBackgroundPlugin {
   }
 }
 
-#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum FaceBackground {
   #[default]
   Empty,
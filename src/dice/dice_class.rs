@@ -0,0 +1,128 @@
+use std::{collections::HashMap, path::Path};
+
+use bevy::{asset::LoadedFolder, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+  constants::DICE_CLASSES_FOLDER,
+  dice::{dice_template::{FaceId, FacePrototype}, status::{register_statuses, StatusRegistry}},
+  loading_screen::AssetStore,
+  states::GameState,
+  utils::ron_asset::{RegisterRonAsset, RonAsset, RonError},
+};
+
+pub struct DiceClassPlugin;
+
+impl Plugin for DiceClassPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .register_ron_asset::<DiceClass>()
+      .add_systems(OnExit(GameState::Loading), collect_dice_classes);
+  }
+}
+
+/// Describes a class of dice, loaded from `assets/classes/*.class.ron`
+#[derive(Asset, TypePath, Deserialize, Clone)]
+pub struct DiceClass {
+  pub name: String,
+  pub hp: ClassHp,
+  /// Exactly six faces, in `FaceId` order
+  pub faces: Vec<FacePrototype>,
+  /// Faces replaced when reaching each level above the first
+  pub upgrades: Vec<Vec<(FaceId, FacePrototype)>>,
+  /// Names of the statuses the dices of this class refuse
+  #[serde(default)]
+  pub immunities: Vec<String>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct ClassHp {
+  pub base: u32,
+  pub per_level: u32,
+}
+
+impl RonAsset for DiceClass {
+  const EXTENSIONS: &'static [&'static str] = &["class.ron"];
+
+  fn validate(&self) -> Result<(), RonError> {
+    if self.faces.len() != 6 {
+      return Err(format!("Dice class {} has {} faces instead of 6", self.name, self.faces.len()).into());
+    }
+    let mut statuses = StatusRegistry::default();
+    register_statuses(&mut statuses);
+    for immunity in &self.immunities {
+      if !statuses.known_statuses().any(|status| status.name == immunity) {
+        return Err(format!("Dice class {} is immune to unknown status {immunity}", self.name).into());
+      }
+    }
+    Ok(())
+  }
+}
+
+impl DiceClass {
+  pub fn max_level(&self) -> u32 {
+    self.upgrades.len() as u32 + 1
+  }
+
+  pub fn hp(&self, level: u32) -> u32 {
+    self.hp.base + self.hp.per_level * level
+  }
+
+  /// Faces replaced when reaching the level, from 2 to `max_level`
+  pub fn upgrade(&self, level: u32) -> &[(FaceId, FacePrototype)] {
+    &self.upgrades[level as usize - 2]
+  }
+
+  pub fn faces(&self) -> [FacePrototype; 6] {
+    self.faces.clone().try_into().unwrap()
+  }
+}
+
+/// Every loaded class by name
+#[derive(Resource, Clone)]
+pub struct DiceClasses(HashMap<String, DiceClass>);
+
+impl DiceClasses {
+  /// `None` for names missing from the class files, which can come from a save or the campaign
+  pub fn get(&self, name: &str) -> Option<&DiceClass> {
+    self.0.get(name)
+  }
+
+  /// Classes sorted by name, so random picks follow the seed
+  pub fn sorted(&self) -> Vec<&DiceClass> {
+    let mut classes: Vec<&DiceClass> = self.0.values().collect();
+    classes.sort_by(|a, b| a.name.cmp(&b.name));
+    classes
+  }
+
+  /// Reads the class files straight from disk, for when there is no app to load them
+  pub fn read_dir(path: &Path) -> Result<Self, RonError> {
+    let mut classes = HashMap::new();
+    for entry in std::fs::read_dir(path)? {
+      let path = entry?.path();
+      if !path.to_string_lossy().ends_with(".class.ron") { continue }
+      let class = DiceClass::read(&path)?;
+      classes.insert(class.name.clone(), class);
+    }
+    Ok(Self(classes))
+  }
+
+  pub fn read_default() -> Result<Self, RonError> {
+    Self::read_dir(&Path::new("assets").join(DICE_CLASSES_FOLDER))
+  }
+}
+
+pub fn collect_dice_classes(
+  mut commands: Commands,
+  asset_store: Res<AssetStore>,
+  folders: Res<Assets<LoadedFolder>>,
+  classes: Res<Assets<DiceClass>>,
+) {
+  let folder = folders.get(&asset_store.get::<LoadedFolder>(DICE_CLASSES_FOLDER)).unwrap();
+  let classes = folder.handles
+    .iter()
+    .filter_map(|handle| classes.get(handle.id().try_typed::<DiceClass>().ok()?))
+    .map(|class| (class.name.clone(), class.clone()))
+    .collect();
+  commands.insert_resource(DiceClasses(classes));
+}
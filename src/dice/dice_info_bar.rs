@@ -5,7 +5,7 @@ use bevy::{ecs::relationship::RelatedSpawnerCommands, prelude::*};
 use super::events::SpawnDices;
 use super::dice_instance::Dice;
 
-use crate::{camera::BattleCamera, constants::dice_info_bar::*, dice::{dice_instance::Health, status::{Burning, Regeneration, Status}}, states::GameState};
+use crate::{camera::BattleCamera, constants::dice_info_bar::*, dice::{dice_instance::Health, status::Status}, states::InBattle};
 
 pub struct DiceInfoBarPlugin;
 
@@ -13,12 +13,8 @@ impl Plugin for DiceInfoBarPlugin {
   fn build(&self, app: &mut App) {
     app
       .add_systems(Update, spawn_dice_info_bars.run_if(on_event::<SpawnDices>))
-      .add_systems(PostUpdate, update_dice_info_bar_positions.run_if(in_state(GameState::Battle)))
-      .add_systems(Update, update_health_bar_indicator)
-      .add_systems(Update, (
-        update_status_intensity::<Burning>,
-        update_status_intensity::<Regeneration>,
-      ));
+      .add_systems(PostUpdate, update_dice_info_bar_positions.run_if(in_state(InBattle)))
+      .add_systems(Update, (update_health_bar_indicator, animate_status_pulse));
   }
 }
 
@@ -108,6 +104,43 @@ pub struct StatusIntensity<S: Status> {
   _marker: PhantomData<S>,
 }
 
+#[derive(Component)]
+#[relationship(relationship_target = StatusDuration<S>)]
+pub struct StatusDurationOf<S: Status> {
+  #[relationship]
+  dice: Entity,
+  _marker: PhantomData<S>,
+}
+
+impl<S: Status> StatusDurationOf<S> {
+  pub fn new(dice: Entity) -> Self {
+    Self {
+      dice,
+      _marker: PhantomData::<S>,
+    }
+  }
+}
+
+#[derive(Component)]
+#[relationship_target(relationship = StatusDurationOf<S>, linked_spawn)]
+pub struct StatusDuration<S: Status> {
+  #[relationship]
+  text: Entity,
+  _marker: PhantomData<S>,
+}
+
+/// Briefly grows the status icon when its status resolves
+#[derive(Component)]
+pub struct StatusPulse {
+  timer: Timer,
+}
+
+impl Default for StatusPulse {
+  fn default() -> Self {
+    Self { timer: Timer::from_seconds(STATUS_PULSE_DURATION, TimerMode::Once) }
+  }
+}
+
 #[derive(Component)]
 #[relationship(relationship_target = HealthIndicator)]
 pub struct HealthIndicatorOf {
@@ -251,15 +284,32 @@ fn health_color(percentage: f32) -> BackgroundColor {
   )
 }
 
-fn update_status_intensity<S: Status>(
-  statuses: Query<(&S, &StatusIntensity<S>), Changed<S>>,
+#[allow(clippy::type_complexity)]
+pub fn update_status_overlays<S: Status>(
+  statuses: Query<(&S, &StatusIntensity<S>, &StatusDuration<S>), Changed<S>>,
   mut texts: Query<&mut Text>,
 ) {
-  for (status, intensity) in statuses {
-    if let Ok(mut text) = texts.get_mut(intensity.text) {
-      if let Some(intensity) = status.intensity() {
-        text.0 = format!("{intensity}");
+  for (status, intensity, duration) in statuses {
+    for (text, value) in [(intensity.text, status.intensity()), (duration.text, status.duration())] {
+      if let (Ok(mut text), Some(value)) = (texts.get_mut(text), value) {
+        text.0 = format!("{value}");
       }
     }
   }
 }
+
+fn animate_status_pulse(
+  mut commands: Commands,
+  time: Res<Time>,
+  icons: Query<(Entity, &mut StatusPulse, &mut Transform)>,
+) {
+  for (entity, mut pulse, mut transform) in icons {
+    pulse.timer.tick(time.delta());
+    let t = pulse.timer.fraction();
+    transform.scale = Vec3::splat(1.0 + STATUS_PULSE_SCALE * (t * std::f32::consts::PI).sin());
+    if pulse.timer.finished() {
+      transform.scale = Vec3::ONE;
+      commands.entity(entity).remove::<StatusPulse>();
+    }
+  }
+}
@@ -1,10 +1,11 @@
 use bevy::prelude::*;
 use bevy_defer::{fetch, AccessError, AsyncAccess, AsyncWorld};
+use serde::{Deserialize, Serialize};
 
 use crate::dice::events::SpawnDices;
 use crate::dice::Gridable;
 use crate::manage::plugin::{EnemyTeam, MyTeam};
-use crate::states::GameState;
+use crate::states::InBattle;
 use crate::utils::*;
 
 use super::animation::get_dice_entity;
@@ -22,13 +23,13 @@ impl Plugin for DiceInstancePlugin {
     app
       .insert_resource(DiceEntityMap::default())
       .insert_resource(Rows::default())
-      .add_systems(OnEnter(GameState::Battle), spawn_dices)
-      .add_systems(OnExit(GameState::Battle), despawn_dices)
+      .add_systems(OnEnter(InBattle), spawn_dices)
+      .add_systems(OnExit(InBattle), despawn_dices)
       .register_listener(despawn_dead_dice);
   }
 }
 
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct DiceID {
   pub team_id: usize,
   pub dice_id: usize,
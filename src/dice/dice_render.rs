@@ -5,7 +5,7 @@ use bevy::prelude::*;
 
 use crate::constants::DICE_SIZE;
 use crate::dice::dice_instance::{DiceEntityMap, Health};
-use crate::dice::{Dice, DiceID, Face};
+use crate::dice::{Dice, DiceID, DiceTemplate, Face, StatusRegistry};
 use crate::manage::DiceGrid;
 use crate::utils::tooltip::TooltipOf;
 
@@ -119,7 +119,8 @@ pub fn spawn_dice(
   mut commands: Commands,
   mut materials: ResMut<Assets<DiceMaterial>>,
   mut dice_entity_map: ResMut<DiceEntityMap>,
-  healths: Query<&Health>,
+  templates: Query<(&Health, &DiceTemplate)>,
+  statuses: Res<StatusRegistry>,
   faces: Query<&Face>,
   children: Query<&Children>,
 ) {
@@ -134,7 +135,7 @@ pub fn spawn_dice(
 
   let mesh = DiceMeshBuilder.build();
   let handle = meshes.add(mesh.clone());
-  let health = healths.get(template_entity).unwrap();
+  let (health, template) = templates.get(template_entity).unwrap();
 
   let dice_entity = commands.spawn((
       Name::new("Dice instance"),
@@ -160,5 +161,15 @@ pub fn spawn_dice(
       DiceGrid::spawn(commands, dice_entity);
     });
 
+  let immunities = template.immunities.clone();
+  let statuses = statuses.clone();
+  commands.entity(dice_entity).queue(move |mut entity: EntityWorldMut| {
+    for immunity in &immunities {
+      if let Err(error) = statuses.make_immune(&mut entity, immunity) {
+        warn!("Failed to make {dice_id:?} immune: {error}");
+      }
+    }
+  });
+
   dice_entity_map.0.insert(dice_id, dice_entity);
 }
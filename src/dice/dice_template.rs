@@ -1,6 +1,7 @@
 use bevy::{ecs::relationship::RelatedSpawnerCommands, prelude::*};
+use serde::{Deserialize, Serialize};
 
-use crate::dice::{background::FaceBackground, dice_instance::Health, dice_template::{face_prototypes::{ATTACK_STRONG, ATTACK_STRONG_CRUEL, ATTACK_WEAK, DEFEND, FIRE_STRONG, FIRE_WEAK, REGEN_STRONG, REGEN_WEAK}, face_sets::*}, face::Face, Gridable};
+use crate::{dice::{background::FaceBackground, dice_class::{DiceClass, DiceClasses}, dice_instance::Health, face::Face, Gridable}, utils::ron_asset::RonError};
 
 use super::action::Action;
 
@@ -12,7 +13,13 @@ impl Plugin for DiceTemplatePlugin {
 }
 
 #[derive(Component, Clone)]
-pub struct DiceTemplate;
+pub struct DiceTemplate {
+  /// `None` for dices that were not built from a class
+  pub class: Option<String>,
+  pub level: u32,
+  /// Names of the statuses the dice refuses in battle
+  pub immunities: Vec<String>,
+}
 
 impl Gridable for DiceTemplate {
   fn grid(&self) -> Vec<(i16, i16)> {
@@ -21,6 +28,7 @@ impl Gridable for DiceTemplate {
 }
 
 #[allow(dead_code)]
+#[derive(Clone, Copy, Deserialize)]
 pub enum FaceId {
   Top = 0,
   Left = 1,
@@ -30,21 +38,31 @@ pub enum FaceId {
   FarBottom = 5,
 }
 
-#[derive(Default, Clone, Copy)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub struct DiceTemplateBuilder {
   faces: Option<[FacePrototype; 6]>,
   hp: Option<u32>,
+  #[serde(default)]
+  class: Option<String>,
+  #[serde(default)]
+  level: Option<u32>,
+  #[serde(default)]
+  immunities: Vec<String>,
 }
 
 impl DiceTemplateBuilder {
-  pub fn spawn(self, commands: &mut RelatedSpawnerCommands<ChildOf>, images: &mut Assets<Image>) {
+  pub fn spawn(&self, commands: &mut RelatedSpawnerCommands<ChildOf>, images: &mut Assets<Image>) {
     assert!(self.hp.is_some());
     assert!(self.faces.is_some());
 
     commands
       .spawn((
         Name::new("Dice template"),
-        DiceTemplate,
+        DiceTemplate {
+          class: self.class.clone(),
+          level: self.level.unwrap_or(1),
+          immunities: self.immunities.clone(),
+        },
         Health::new(self.hp.unwrap()),
       ))
       .with_children(|commands| {
@@ -54,6 +72,34 @@ impl DiceTemplateBuilder {
       });
   }
 
+  /// Templates are always at full health between battles, so only the max health is kept
+  pub fn from_template(template: &DiceTemplate, template_faces: &Children, health: &Health, faces: &Query<&Face>) -> Self {
+    let face_set = template_faces
+      .iter()
+      .map(|face| faces.get(face).unwrap().prototype)
+      .collect::<Vec<_>>();
+    Self {
+      class: template.class.clone(),
+      level: Some(template.level),
+      immunities: template.immunities.clone(),
+      ..default()
+    }
+      .with_face_set(face_set.try_into().unwrap())
+      .with_hp(health.max)
+  }
+
+  pub fn faces(&self) -> [FacePrototype; 6] {
+    self.faces.unwrap()
+  }
+
+  pub fn hp(&self) -> u32 {
+    self.hp.unwrap()
+  }
+
+  pub fn immunities(&self) -> &[String] {
+    &self.immunities
+  }
+
   pub fn with_hp(mut self, hp: u32) -> Self {
     self.hp = Some(hp);
     self
@@ -69,86 +115,46 @@ impl DiceTemplateBuilder {
     self
   }
 
-  pub fn berserker(level: u32) -> Self {
-    assert!((1..=4).contains(&level));
-    let mut ret = Self::default()
-      .with_hp(5 + level)
-      .with_face_set(BERSERKER);
-    if level == 1 { return ret }
-    ret = ret.with_face(FaceId::Middle, ATTACK_STRONG);
-    if level == 2 { return ret }
-    ret = ret
-      .with_face(FaceId::Top, DEFEND);
-    if level == 3 { return ret }
-    ret
-      .with_face(FaceId::Left, ATTACK_STRONG_CRUEL.with_pips(3))
-      .with_face(FaceId::Right, ATTACK_STRONG_CRUEL.with_pips(3))
-  }
-
-  pub fn paladin(level: u32) -> Self {
-    assert!((1..=4).contains(&level));
-    let mut ret = Self::default()
-      .with_hp(4 + 2 * level)
-      .with_face_set(PALADIN);
-    if level == 1 { return ret }
-    ret = ret.with_face(FaceId::Left, ATTACK_STRONG);
-    if level == 2 { return ret }
-    ret = ret
-      .with_face(FaceId::Top, ATTACK_WEAK)
-      .with_face(FaceId::NearBottom, ATTACK_WEAK);
-    if level == 3 { return ret }
-    ret
-      .with_face(FaceId::Left, ATTACK_STRONG_CRUEL.with_pips(3))
-  }
-
-  pub fn mage(level: u32) -> Self {
-    assert!((1..=4).contains(&level));
-    let mut ret = Self::default()
-      .with_hp(2 + level)
-      .with_face_set(MAGE);
-    if level == 1 { return ret }
-    ret = ret.with_face(FaceId::NearBottom, FIRE_WEAK);
-    if level == 2 { return ret }
-    ret = ret
-      .with_face(FaceId::Top, FIRE_WEAK);
-    if level == 3 { return ret }
-    ret
-      .with_face(FaceId::Left, FIRE_STRONG.with_pips(3))
-      .with_face(FaceId::Right, FIRE_STRONG.with_pips(3))
-  }
-
-  pub fn cleric(level: u32) -> Self {
-    assert!((1..=4).contains(&level));
-    let mut ret = Self::default()
-      .with_hp(3 + 2 * level)
-      .with_face_set(CLERIC);
-    if level == 1 { return ret }
-    ret = ret.with_face(FaceId::Middle, REGEN_WEAK);
-    if level == 2 { return ret }
-    ret = ret.with_face(FaceId::Top, REGEN_WEAK);
-    if level == 3 { return ret }
-    ret
-      .with_face(FaceId::Left, REGEN_STRONG.with_pips(3))
-      .with_face(FaceId::Right, REGEN_STRONG.with_pips(3))
+  /// Rejects a class missing from `classes`, a level it does not have or other immunities, for builders read from a save
+  pub fn validate_class(&self, classes: &DiceClasses) -> Result<(), RonError> {
+    let Some(name) = &self.class else {
+      if !self.immunities.is_empty() {
+        return Err("Only dices of a class have immunities".into());
+      }
+      return Ok(())
+    };
+    let class = classes.get(name).ok_or_else(|| format!("Unknown dice class {name}"))?;
+    let level = self.level.unwrap_or(1);
+    if !(1..=class.max_level()).contains(&level) {
+      return Err(format!("{name} of level {level}, outside of 1 to {}", class.max_level()).into());
+    }
+    // The immunities of the class were checked when it loaded
+    if self.immunities != class.immunities {
+      return Err(format!("{name} has other immunities than its class").into());
+    }
+    Ok(())
   }
 
-  pub fn rogue(level: u32) -> Self {
-    assert!((1..=4).contains(&level));
-    let mut ret = Self::default()
-      .with_hp(3 + level)
-      .with_face_set(ROGUE);
-    if level == 1 { return ret }
-    ret = ret.with_face(FaceId::Middle, ATTACK_WEAK);
-    if level == 2 { return ret }
-    ret = ret.with_face(FaceId::Top, ATTACK_WEAK);
-    if level == 3 { return ret }
+  pub fn from_class(class: &DiceClass, level: u32) -> Self {
+    assert!((1..=class.max_level()).contains(&level));
+    let mut ret = Self {
+      class: Some(class.name.clone()),
+      level: Some(level),
+      immunities: class.immunities.clone(),
+      ..default()
+    }
+      .with_hp(class.hp(level))
+      .with_face_set(class.faces());
+    for level in 2..=level {
+      for &(face_id, face) in class.upgrade(level) {
+        ret = ret.with_face(face_id, face);
+      }
+    }
     ret
-      .with_face(FaceId::NearBottom, ATTACK_WEAK)
-      .with_face(FaceId::Left, ATTACK_STRONG_CRUEL)
   }
 }
 
-#[derive(PartialEq, Eq, Default, Clone, Copy, Hash, Debug)]
+#[derive(PartialEq, Eq, Default, Clone, Copy, Hash, Debug, Serialize, Deserialize)]
 pub struct FacePrototype {
   pub action: Action,
   pub pips: Option<u32>,
@@ -160,14 +166,8 @@ impl FacePrototype {
     Self { action, pips, background: FaceBackground::Empty }
   }
 
-  const fn with_pips(mut self, pips: u32) -> Self {
-    self.pips = Some(pips);
-    self
-  }
-
-  const fn with_background(mut self, background: FaceBackground) -> Self {
-    self.background = background;
-    self
+  pub const fn with_background(self, background: FaceBackground) -> Self {
+    Self { background, ..self }
   }
 
   pub fn description(&self) -> String {
@@ -181,69 +181,26 @@ impl FacePrototype {
 }
 
 pub mod face_prototypes {
-  use crate::dice::{background::FaceBackground, dice_template::FacePrototype, Action};
+  use crate::dice::{dice_template::FacePrototype, Action, FaceBackground};
 
-  pub const EMPTY: FacePrototype = FacePrototype::new(Action::Empty, None);
   pub const ATTACK_WEAK: FacePrototype = FacePrototype::new(Action::Attack, Some(1));
-  pub const ATTACK_DOUBLE: FacePrototype = FacePrototype::new(Action::Attack, Some(1))
-    .with_background(FaceBackground::Double);
   pub const ATTACK_STRONG: FacePrototype = FacePrototype::new(Action::Attack, Some(2));
-  pub const ATTACK_STRONG_CRUEL: FacePrototype = FacePrototype::new(Action::Attack, Some(2))
-    .with_background(FaceBackground::Cruel);
   pub const DEFEND: FacePrototype = FacePrototype::new(Action::Defend, None);
   pub const REGEN_WEAK: FacePrototype = FacePrototype::new(Action::Regenerate, Some(1));
   pub const REGEN_STRONG: FacePrototype = FacePrototype::new(Action::Regenerate, Some(2));
   pub const FIRE_WEAK: FacePrototype = FacePrototype::new(Action::Fire, Some(1));
   pub const FIRE_STRONG: FacePrototype = FacePrototype::new(Action::Fire, Some(2));
   pub const FIERY: FacePrototype = FacePrototype::new(Action::Fiery, None);
-}
-
-mod face_sets {
-  use crate::dice::{dice_template::FacePrototype};
-  use super::face_prototypes::*;
-
-  pub const BERSERKER: [FacePrototype; 6] = [
-    EMPTY,
-    ATTACK_STRONG_CRUEL,
-    ATTACK_STRONG,
-    EMPTY,
-    EMPTY,
-    EMPTY,
-  ];
-
-  pub const PALADIN: [FacePrototype; 6] = [
-    EMPTY,
-    ATTACK_WEAK,
-    DEFEND,
-    REGEN_WEAK,
-    EMPTY,
-    EMPTY,
-  ];
-
-  pub const MAGE: [FacePrototype; 6] = [
-    EMPTY,
-    FIRE_STRONG,
-    FIRE_STRONG,
-    REGEN_WEAK,
-    EMPTY,
-    EMPTY,
-  ];
-
-  pub const CLERIC: [FacePrototype; 6] = [
-    EMPTY,
-    DEFEND,
-    REGEN_STRONG,
-    EMPTY,
-    EMPTY,
-    EMPTY,
-  ];
-
-  pub const ROGUE: [FacePrototype; 6] = [
-    EMPTY,
-    ATTACK_DOUBLE,
-    ATTACK_DOUBLE,
-    EMPTY,
-    EMPTY,
-    EMPTY,
-  ];
+  pub const BLOCK_WEAK: FacePrototype = FacePrototype::new(Action::Block, Some(1));
+  pub const BLOCK_STRONG: FacePrototype = FacePrototype::new(Action::Block, Some(2));
+  pub const POISON_WEAK: FacePrototype = FacePrototype::new(Action::Poison, Some(1));
+  pub const FREEZE: FacePrototype = FacePrototype::new(Action::Freeze, None);
+  pub const CLEANSE: FacePrototype = FacePrototype::new(Action::Cleanse, None);
+
+  pub const ATTACK_STRONG_CRUEL: FacePrototype = ATTACK_STRONG.with_background(FaceBackground::Cruel);
+  pub const POISON_WEAK_CRUEL: FacePrototype = POISON_WEAK.with_background(FaceBackground::Cruel);
+  pub const ATTACK_STRONG_DOUBLE: FacePrototype = ATTACK_STRONG.with_background(FaceBackground::Double);
+  pub const FIRE_STRONG_DOUBLE: FacePrototype = FIRE_STRONG.with_background(FaceBackground::Double);
+  pub const REGEN_STRONG_DOUBLE: FacePrototype = REGEN_STRONG.with_background(FaceBackground::Double);
+  pub const BLOCK_STRONG_DOUBLE: FacePrototype = BLOCK_STRONG.with_background(FaceBackground::Double);
 }
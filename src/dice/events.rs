@@ -2,7 +2,7 @@ use bevy::prelude::*;
 
 use crate::utils::*;
 
-use super::DiceID;
+use super::{DiceID, FacePrototype};
 
 pub struct DiceEventsPlugin;
 
@@ -10,7 +10,13 @@ impl Plugin for DiceEventsPlugin {
   fn build(&self, app: &mut App) {
     app
       .add_event_and_listen::<SpawnDices>()
-      .add_event_and_listen::<DiceDied>();
+      .add_event_and_listen::<DiceDied>()
+      .add_event_and_listen::<BeforeDamage>()
+      .add_event_and_listen::<AfterDamage>()
+      .add_event_and_listen::<BeforeHeal>()
+      .add_event_and_listen::<FaceRolled>()
+      .add_event_and_listen::<BeforeResolveDice>()
+      .add_event_and_listen::<AfterResolveDice>();
   }
 }
 
@@ -21,3 +27,55 @@ pub struct SpawnDices;
 pub struct DiceDied {
   pub dice_id: DiceID,
 }
+
+/// Listeners can change the amount or the target, or cancel the damage altogether.
+/// The source is `None` when the damage comes from a status.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct BeforeDamage {
+  pub source: Option<DiceID>,
+  pub target: DiceID,
+  pub amount: u32,
+  pub color: Color,
+  pub cancelled: bool,
+}
+
+/// Damage that went through to the health, after shields
+#[derive(Event, Clone, Copy, Debug)]
+#[allow(unused)]
+pub struct AfterDamage {
+  pub source: Option<DiceID>,
+  pub target: DiceID,
+  pub amount: u32,
+}
+
+/// Triggered for every dice once the roll settled, listeners can change the face that will be resolved
+#[derive(Event, Clone, Copy, Debug)]
+pub struct FaceRolled {
+  #[allow(unused)]
+  pub dice_id: DiceID,
+  pub face: FacePrototype,
+}
+
+/// Triggered before the rolled face of a dice is resolved, cancelling skips the resolution
+#[derive(Event, Clone, Copy, Debug)]
+pub struct BeforeResolveDice {
+  pub dice_id: DiceID,
+  pub face: FacePrototype,
+  pub cancelled: bool,
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+#[allow(unused)]
+pub struct AfterResolveDice {
+  pub dice_id: DiceID,
+  pub face: FacePrototype,
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct BeforeHeal {
+  #[allow(unused)]
+  pub source: Option<DiceID>,
+  pub target: DiceID,
+  pub amount: u32,
+  pub cancelled: bool,
+}
@@ -1,7 +1,6 @@
 use bevy::{ecs::relationship::RelatedSpawnerCommands, prelude::*, render::render_resource::{Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages}};
-use bevy_defer::AccessError;
 
-use crate::{constants::{dice_texture::{FONT_SIZE, OFFSET, PIPS_POSITION, SCALING_FACTOR, TARGET_SIZE}, DICE_FACES_LAYER}, dice::{action::{resolve, ResolutionContext}, dice_template::FacePrototype, DiceID}, loading_screen::AssetStore};
+use crate::{constants::{dice_texture::{FONT_SIZE, OFFSET, PIPS_POSITION, SCALING_FACTOR, TARGET_SIZE}, DICE_FACES_LAYER}, dice::dice_template::FacePrototype, loading_screen::AssetStore};
 
 pub struct FacePlugin;
 
@@ -33,10 +32,6 @@ impl Face {
   pub fn from_other(other: &Self, images: &mut Assets<Image>) -> Self {
     Self::from_prototype(other.prototype, images)
   }
-
-  pub async fn resolve(self, dice_id: DiceID) -> Result<(), AccessError> {
-    resolve(ResolutionContext { face: self.prototype, dice_id }).await
-  }
 }
 
 #[derive(Component)]
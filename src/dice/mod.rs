@@ -2,7 +2,7 @@ pub mod plugin;
 
 mod action;
 mod background;
-mod status;
+pub mod status;
 mod animation;
 mod dice_instance;
 mod dice_render;
@@ -12,12 +12,18 @@ mod dice_info_bar;
 mod roll;
 mod face;
 mod synergy;
+mod simulator;
+mod dice_class;
 
 pub use face::{Face, Gridable, FaceSource};
-pub use dice_template::{DiceTemplate, DiceTemplateBuilder, FacePrototype, face_prototypes};
-pub use dice_instance::{DiceID, Dice};
+pub use dice_template::{DiceTemplate, DiceTemplateBuilder, FaceId, FacePrototype, face_prototypes};
+pub use dice_instance::{DiceID, Dice, Health};
 pub use roll::{roll_dices, resolve_dices};
 pub use action::Action;
-pub use synergy::spawn_synergy_displays;
+pub use background::FaceBackground;
+pub use synergy::{spawn_synergy_displays, SynergyRegistry};
 pub use animation::move_dices_to_rows;
 pub use dice_info_bar::HealthBar;
+pub use simulator::{BattleSimulator, BattleOutcome};
+pub use dice_class::{collect_dice_classes, DiceClasses};
+pub use status::{SavedStatus, StatusKind, StatusRegistry};
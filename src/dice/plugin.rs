@@ -5,6 +5,7 @@ use super::{
   dice_instance::DiceInstancePlugin,
   dice_render::DiceRenderPlugin,
   dice_template::DiceTemplatePlugin,
+  dice_class::DiceClassPlugin,
   events::DiceEventsPlugin,
   dice_info_bar::DiceInfoBarPlugin,
   roll::RollPlugin,
@@ -26,6 +27,7 @@ impl Plugin for DicePlugin {
         DiceInstancePlugin,
         DiceRenderPlugin,
         DiceTemplatePlugin,
+        DiceClassPlugin,
         DiceInfoBarPlugin,
         RollPlugin,
         DiceActionPlugin,
@@ -1,20 +1,29 @@
+use std::collections::HashMap;
+
 use avian3d::prelude::*;
 use futures_::future::{join, join_all};
 use bevy::prelude::*;
 use bevy_defer::{fetch, AccessError, AsyncAccess, AsyncWorld};
+use rand::rngs::StdRng;
 use rand_distr::{Distribution, Normal};
 
-use crate::{camera::SwapBattleCamera, constants::{ANGULAR_VELOCITY_EPSILON, DICE_COUNT, DICE_SIZE, FACE_NORMALS, HEIGHT, LINEAR_VELOCITY_EPSILON, WIDTH}, dice::{move_dices_to_rows, Face}};
+use crate::{battle::replay::{replayed, ReplayEvent, ReplayPlayback, ReplayRecording, RolledDices}, camera::SwapBattleCamera, constants::{ANGULAR_VELOCITY_EPSILON, DICE_SIZE, FACE_NORMALS, HEIGHT, LINEAR_VELOCITY_EPSILON, WIDTH}, dice::{move_dices_to_rows, Face, FacePrototype}, utils::{rng::GameRng, *}};
 
-use super::{animation::{add_physics, get_dice_entity, move_dice_to_middle, move_dice_to_row, orient_dice, remove_physics}, dice_instance::Rows, Dice, DiceID};
+use super::{action::{resolve, ResolutionContext}, animation::{add_physics, get_dice_entity, move_dice_to_middle, move_dice_to_row, orient_dice, remove_physics}, dice_instance::{DiceEntityMap, Rows}, events::{AfterResolveDice, BeforeResolveDice, FaceRolled}, Dice, DiceID};
 
 pub struct RollPlugin;
 
 impl Plugin for RollPlugin {
-  fn build(&self, _app: &mut App) {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<RolledFaces>();
   }
 }
 
+/// Faces the dices landed on this round, after `FaceRolled` listeners had their say
+#[derive(Resource, Default, Clone)]
+struct RolledFaces(HashMap<DiceID, FacePrototype>);
+
 pub async fn set_physics(on: bool) -> Result<(), AccessError> {
   if on {
     AsyncWorld.run_system_cached(add_physics)?;
@@ -25,55 +34,82 @@ pub async fn set_physics(on: bool) -> Result<(), AccessError> {
 }
 
 pub async fn roll_dices() -> Result<(), AccessError> {
-  set_physics(true).await?;
-  AsyncWorld.run_system_cached(set_dice_roll_positions_and_velocities)?;
-  wait_for_dices_to_stop().await.unwrap();
-  set_physics(false).await?;
+  if let Some(rolled) = replayed(ReplayPlayback::next_roll).await {
+    AsyncWorld.run_system_cached_with(place_rolled_dices, rolled)?;
+  } else {
+    set_physics(true).await?;
+    AsyncWorld.run_system_cached(set_dice_roll_positions_and_velocities)?;
+    wait_for_dices_to_stop().await.unwrap();
+    set_physics(false).await?;
 
-  AsyncWorld.run_system_cached(compute_row_positions)?;
+    AsyncWorld.run_system_cached(compute_row_positions)?;
+    AsyncWorld.run_system_cached(record_roll)?;
+  }
   AsyncWorld.send_event(SwapBattleCamera)?;
 
   let (result1, result2) = join(move_dices_to_rows(), orient_dices()).await;
   result1?; result2?;
+
+  trigger_faces_rolled().await
+}
+
+async fn trigger_faces_rolled() -> Result<(), AccessError> {
+  let rows = AsyncWorld.resource::<Rows>().cloned()?;
+  let mut rolled_faces = HashMap::new();
+  for dice_id in rows.team1.into_iter().chain(rows.team2) {
+    let face_rolled = FaceRolled::wrap(FaceRolled { dice_id, face: read_rolled_face(dice_id).await? });
+    AsyncWorld.trigger_event(face_rolled.clone()).await?;
+    rolled_faces.insert(dice_id, face_rolled.get().face);
+  }
+  AsyncWorld.resource::<RolledFaces>().get_mut(|rolled| *rolled = RolledFaces(rolled_faces))?;
   Ok(())
 }
 
+async fn read_rolled_face(dice_id: DiceID) -> Result<FacePrototype, AccessError> {
+  let entity = get_dice_entity(dice_id).await?;
+  let face_id = fetch!(entity, Transform).get(|transform| get_face_id(transform.rotation))?;
+  let face_entity = fetch!(entity, Children).get(|children| (*children)[face_id])?;
+  fetch!(face_entity, Face).get(|face| face.prototype)
+}
+
 fn set_dice_roll_positions_and_velocities(
   mut dices: Query<(&mut Transform, &mut LinearVelocity, &mut AngularVelocity, &Dice)>,
+  mut rng: ResMut<GameRng>,
 ) {
-  let dice_positions_team_1 = [
-    Vec3::new((-WIDTH + DICE_SIZE * 1.5) / 2.0, -HEIGHT / 4.0, DICE_SIZE * 1.5,),
-    Vec3::new((-WIDTH + DICE_SIZE * 1.5) / 2.0, -HEIGHT / 4.0 + DICE_SIZE * 3.0, DICE_SIZE * 1.5,),
-    Vec3::new((-WIDTH + DICE_SIZE * 1.5) / 2.0, -HEIGHT / 4.0 - DICE_SIZE * 3.0, DICE_SIZE * 1.5,),
-    Vec3::new((-WIDTH + DICE_SIZE * 1.5) / 2.0, -HEIGHT / 4.0 + DICE_SIZE * 1.5, DICE_SIZE * 1.5,),
-    Vec3::new((-WIDTH + DICE_SIZE * 1.5) / 2.0, -HEIGHT / 4.0 - DICE_SIZE * 1.5, DICE_SIZE * 1.5,),
-  ];
-  let dice_positions_team_2 = dice_positions_team_1.map(|mut vec| {
-    vec.x *= -1.0;
-    vec.y *= -1.0;
-    vec
+  let rng = rng.roll();
+  let team_sizes = dices.iter().fold([0; 2], |mut sizes, (.., dice)| {
+    sizes[dice.id().team_id] += 1;
+    sizes
   });
+  // Spread the dices of a team along the side of its half of the table
+  let dice_position = |DiceID { team_id, dice_id }: DiceID| {
+    let count = team_sizes[team_id];
+    let spacing = (DICE_SIZE * 1.5).min((HEIGHT / 2.0 - DICE_SIZE * 2.0) / (count.max(2) - 1) as f32);
+    let offset = (dice_id as f32 - (count - 1) as f32 / 2.0) * spacing;
+    let position = Vec3::new((-WIDTH + DICE_SIZE * 1.5) / 2.0, -HEIGHT / 4.0 + offset, DICE_SIZE * 1.5);
+    if team_id == 0 { position } else { Vec3::new(-position.x, -position.y, position.z) }
+  };
 
   for (mut transform, mut linear_velocity, mut angular_velocity, dice) in &mut dices {
     if dice.id().team_id == 0 {
-      *transform = Transform::from_translation(dice_positions_team_1[dice.id().dice_id]).with_scale(Vec3::new(DICE_SIZE, DICE_SIZE, DICE_SIZE));
+      *transform = Transform::from_translation(dice_position(dice.id())).with_scale(Vec3::new(DICE_SIZE, DICE_SIZE, DICE_SIZE));
       *linear_velocity = LinearVelocity::from(Vec3::new(
-        random(30.0 * DICE_SIZE, 10.0 * DICE_SIZE),
-        random(0.0, 5.0 * DICE_SIZE),
-        random(10.0 * DICE_SIZE, 10.0 * DICE_SIZE),
+        random(rng, 30.0 * DICE_SIZE, 10.0 * DICE_SIZE),
+        random(rng, 0.0, 5.0 * DICE_SIZE),
+        random(rng, 10.0 * DICE_SIZE, 10.0 * DICE_SIZE),
       ));
     } else {
-      *transform = Transform::from_translation(dice_positions_team_2[dice.id().dice_id]).with_scale(Vec3::new(DICE_SIZE, DICE_SIZE, DICE_SIZE));
+      *transform = Transform::from_translation(dice_position(dice.id())).with_scale(Vec3::new(DICE_SIZE, DICE_SIZE, DICE_SIZE));
       *linear_velocity = LinearVelocity::from(Vec3::new(
-        random(-30.0 * DICE_SIZE, 10.0 * DICE_SIZE),
-        random(0.0, 5.0 * DICE_SIZE),
-        random(10.0 * DICE_SIZE, 10.0 * DICE_SIZE),
+        random(rng, -30.0 * DICE_SIZE, 10.0 * DICE_SIZE),
+        random(rng, 0.0, 5.0 * DICE_SIZE),
+        random(rng, 10.0 * DICE_SIZE, 10.0 * DICE_SIZE),
       ));
     }
     *angular_velocity = AngularVelocity::from(Vec3::new(
-        random(0.0, 20.0),
-        random(0.0, 20.0),
-        random(0.0, 20.0),
+        random(rng, 0.0, 20.0),
+        random(rng, 0.0, 20.0),
+        random(rng, 0.0, 20.0),
     ));
   }
 }
@@ -81,7 +117,7 @@ fn set_dice_roll_positions_and_velocities(
 pub async fn resolve_dices() -> Result<(), AccessError> {
   let rows = AsyncWorld.resource::<Rows>().cloned().unwrap();
 
-  for i in 0..DICE_COUNT {
+  for i in 0..rows.team1.len().max(rows.team2.len()) {
     async fn resolve(dice_id: DiceID) -> Result<(), AccessError> {
       move_dice_to_middle(dice_id).await?;
       let _ = resolve_dice(dice_id).await;
@@ -99,13 +135,21 @@ pub async fn resolve_dices() -> Result<(), AccessError> {
 }
 
 async fn resolve_dice(dice_id: DiceID) -> Result<(), AccessError> {
-  let entity = get_dice_entity(dice_id).await?;
-  let face_id = fetch!(entity, Transform).get(|transform| get_face_id(transform.rotation))?;
-  let face_entity = fetch!(entity, Children).get(|children| (*children)[face_id])?;
-  let face = fetch!(face_entity, Face).get(|face| face.clone())?;
+  let face = match AsyncWorld.resource::<RolledFaces>().get(|rolled| rolled.0.get(&dice_id).copied())? {
+    Some(face) => face,
+    None => read_rolled_face(dice_id).await?,
+  };
+
+  let before_resolve = BeforeResolveDice::wrap(BeforeResolveDice { dice_id, face, cancelled: false });
+  AsyncWorld.trigger_event(before_resolve.clone()).await?;
+  let BeforeResolveDice { face, cancelled, .. } = before_resolve.get();
+  if cancelled {
+    return Ok(())
+  }
 
-  face.resolve(dice_id).await?;
+  resolve(ResolutionContext { face, dice_id }).await?;
 
+  AsyncWorld.trigger_event(AfterResolveDice::wrap(AfterResolveDice { dice_id, face })).await?;
   Ok(())
 }
 
@@ -167,6 +211,44 @@ fn compute_row_positions(
   }
 }
 
+fn record_roll(
+  dices: Query<(&Transform, &Dice)>,
+  rows: Res<Rows>,
+  recording: Option<ResMut<ReplayRecording>>,
+) {
+  let Some(mut recording) = recording else { return };
+  let faces = dices
+    .iter()
+    .map(|(transform, dice)| (dice.id(), get_face_id(transform.rotation)))
+    .collect();
+  recording.events.push(ReplayEvent::DicesRolled(RolledDices {
+    rows: [rows.team1.clone(), rows.team2.clone()],
+    faces,
+  }));
+}
+
+fn place_rolled_dices(
+  In(RolledDices { rows: [team1, team2], faces }): In<RolledDices>,
+  mut dices: Query<(&mut Transform, &mut Dice)>,
+  dice_entity_map: Res<DiceEntityMap>,
+  mut rows: ResMut<Rows>,
+) {
+  for (dice_id, face_id) in faces {
+    let Some(&entity) = dice_entity_map.0.get(&dice_id) else { continue };
+    let Ok((mut transform, _)) = dices.get_mut(entity) else { continue };
+    transform.rotation = Quat::from_rotation_arc(FACE_NORMALS[face_id], Vec3::Z);
+  }
+
+  for row in [&team1, &team2] {
+    for (row_position, dice_id) in row.iter().enumerate() {
+      let Some(&entity) = dice_entity_map.0.get(dice_id) else { continue };
+      let Ok((_, mut dice)) = dices.get_mut(entity) else { continue };
+      dice.set_row_position(row_position);
+    }
+  }
+  *rows = Rows { team1, team2 };
+}
+
 pub fn get_face_id(rotation: Quat) -> usize {
   let mut face_id = 0;
   let mut max_dot = -1.0;
@@ -180,7 +262,7 @@ pub fn get_face_id(rotation: Quat) -> usize {
   face_id
 }
 
-fn random(mean: f32, std_dev: f32) -> f32 {
+fn random(rng: &mut StdRng, mean: f32, std_dev: f32) -> f32 {
   let normal = Normal::<f32>::new(mean, std_dev).unwrap();
-  normal.sample(&mut rand::thread_rng())
+  normal.sample(rng)
 }
@@ -0,0 +1,499 @@
+use std::{any::{Any, TypeId}, fmt::Display};
+
+use bevy::prelude::*;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+  battle::{
+    rules::{action_rule, choose_target, heal, repeats, take_damage, tick_status, absorb_damage, ActionEffect, ActionTarget, NewStatus, StatusEffect},
+    BeforeRollDices, EndRound, StartGame, StartRound,
+  },
+  dice::{
+    action::GetPips,
+    background::FaceBackground,
+    dice_instance::Health,
+    events::{AfterDamage, AfterResolveDice, BeforeDamage, BeforeHeal, BeforeResolveDice, DiceDied, FaceRolled},
+    status::{register_statuses, RegisterStatus, Shield, Status, StatusRegistry},
+    synergy::{register_synergies, RegisterSynergy, Synergy},
+    DiceID, DiceTemplateBuilder, FacePrototype,
+  },
+  utils::rng::GameRng,
+};
+
+// Battles where neither team can finish the other (e.g. only empty faces) are called a draw
+const MAX_ROUNDS: u32 = 100;
+
+#[derive(Clone, Copy, Debug)]
+pub enum BattleLogEntry {
+  FaceRolled { dice_id: DiceID, face: FacePrototype },
+  Damage { dice_id: DiceID, amount: u32 },
+  Heal { dice_id: DiceID, amount: u32 },
+  StatusApplied { dice_id: DiceID, status: &'static str },
+  StatusRefused { dice_id: DiceID, status: &'static str },
+  DiceDied { dice_id: DiceID },
+}
+
+impl Display for BattleLogEntry {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::FaceRolled { dice_id, face } => write!(f, "{dice_id:?} rolled {:?} {:?}", face.action, face.pips),
+      Self::Damage { dice_id, amount } => write!(f, "{dice_id:?} took {amount} damage"),
+      Self::Heal { dice_id, amount } => write!(f, "{dice_id:?} healed {amount}"),
+      Self::StatusApplied { dice_id, status } => write!(f, "{dice_id:?} got {status}"),
+      Self::StatusRefused { dice_id, status } => write!(f, "{dice_id:?} is immune to {status}"),
+      Self::DiceDied { dice_id } => write!(f, "{dice_id:?} died"),
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RoundLog {
+  pub round: u32,
+  pub entries: Vec<BattleLogEntry>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BattleOutcome {
+  /// Team id of the winner, `None` if the battle hit the round limit
+  pub winner: Option<usize>,
+  pub rounds: Vec<RoundLog>,
+}
+
+/// The statuses of the dice are components of its `entity` in the simulator world
+#[derive(Clone)]
+struct SimulatedDice {
+  id: DiceID,
+  entity: Entity,
+  faces: [FacePrototype; 6],
+  health: Health,
+}
+
+impl SimulatedDice {
+  fn alive(&self) -> bool {
+    self.health.current > 0
+  }
+}
+
+/// Headless version of a status or synergy listener, run for the events of type `event`
+#[derive(Clone, Copy)]
+struct Listener {
+  event: TypeId,
+  trigger: fn(&mut BattleSimulator, &mut dyn Any),
+}
+
+/// Collects the listeners of every status and synergy, in the order the app registers them
+#[derive(Default)]
+struct Listeners {
+  statuses: StatusRegistry,
+  listeners: Vec<Listener>,
+}
+
+impl RegisterStatus for Listeners {
+  fn register_status<S: Status>(&mut self) -> &mut Self {
+    self.statuses.register_status::<S>();
+    self.listeners.push(Listener {
+      event: TypeId::of::<S::TriggerEvent>(),
+      trigger: BattleSimulator::trigger_status::<S>,
+    });
+    self
+  }
+}
+
+impl RegisterSynergy for Listeners {
+  fn register<S: Synergy>(&mut self) -> &mut Self {
+    self.listeners.push(Listener {
+      event: TypeId::of::<S::TriggerEvent>(),
+      trigger: BattleSimulator::trigger_synergy::<S>,
+    });
+    self
+  }
+}
+
+/// Resolves a whole battle without rendering or physics.
+/// Faces are picked uniformly instead of throwing the dice and the row order is shuffled,
+/// everything else goes through the same `rules` and the same statuses and synergies as `battle::sequence::flow`.
+pub struct BattleSimulator {
+  world: World,
+  dices: Vec<SimulatedDice>,
+  statuses: StatusRegistry,
+  listeners: Vec<Listener>,
+  rng: GameRng,
+  rounds: Vec<RoundLog>,
+}
+
+impl BattleSimulator {
+  pub fn new(
+    team1: impl IntoIterator<Item = DiceTemplateBuilder>,
+    team2: impl IntoIterator<Item = DiceTemplateBuilder>,
+    seed: u64,
+  ) -> Self {
+    // Statuses run before synergies, like `StatusPlugin` is added before `SynergyPlugin`
+    let mut listeners = Listeners::default();
+    register_statuses(&mut listeners);
+    register_synergies(&mut listeners);
+
+    let mut world = World::new();
+    let mut dices = Vec::new();
+    for (team_id, team) in [team1.into_iter().collect::<Vec<_>>(), team2.into_iter().collect()].into_iter().enumerate() {
+      for (dice_id, builder) in team.into_iter().enumerate() {
+        let id = DiceID { team_id, dice_id };
+        let mut entity = world.spawn_empty();
+        for immunity in builder.immunities() {
+          if let Err(error) = listeners.statuses.make_immune(&mut entity, immunity) {
+            warn!("Failed to make {id:?} immune: {error}");
+          }
+        }
+        dices.push(SimulatedDice {
+          id,
+          entity: entity.id(),
+          faces: builder.faces(),
+          health: Health::new(builder.hp()),
+        });
+      }
+    }
+
+    Self {
+      world,
+      dices,
+      statuses: listeners.statuses,
+      listeners: listeners.listeners,
+      rng: GameRng::new(seed),
+      rounds: Vec::new(),
+    }
+  }
+
+  pub fn run(mut self) -> BattleOutcome {
+    self.rounds.push(RoundLog::default());
+    self.trigger(StartGame);
+
+    for round in 1..=MAX_ROUNDS {
+      self.rounds.push(RoundLog { round, ..Default::default() });
+      self.trigger(StartRound { round });
+      self.trigger(BeforeRollDices { round });
+      let rows = self.roll_dices();
+      self.resolve_dices(rows);
+      self.trigger(EndRound { round });
+
+      if let Some(winner) = self.winner() {
+        return BattleOutcome { winner: Some(winner), rounds: self.rounds };
+      }
+    }
+    BattleOutcome { winner: None, rounds: self.rounds }
+  }
+
+  fn log(&mut self, entry: BattleLogEntry) {
+    self.rounds.last_mut().unwrap().entries.push(entry);
+  }
+
+  /// Runs every listener of the event in order, each one gets the event as the previous one left it
+  fn trigger<E: Event + Copy>(&mut self, mut event: E) -> E {
+    let listeners = self.listeners
+      .iter()
+      .filter(|listener| listener.event == TypeId::of::<E>())
+      .copied()
+      .collect::<Vec<_>>();
+    for listener in listeners {
+      (listener.trigger)(self, &mut event);
+    }
+    event
+  }
+
+  /// Like the listener of `register_status`: applies the effect of the status on every dice it triggers for,
+  /// then counts it down and lets it change the event
+  fn trigger_status<S: Status>(&mut self, event: &mut dyn Any) {
+    let Some(event) = event.downcast_mut::<S::TriggerEvent>() else { return };
+    let triggered = self.alive_where(|_| true)
+      .into_iter()
+      .filter_map(|index| {
+        let dice = &self.dices[index];
+        let status = *self.world.get::<S>(dice.entity)?;
+        status.trigger_condition(dice.id, *event).then_some((index, status))
+      })
+      .collect::<Vec<_>>();
+
+    for (index, status) in triggered {
+      if let Some(effect) = status.effect() {
+        self.resolve_status_effect(index, effect);
+      }
+      let dice = &self.dices[index];
+      let mut entity = self.world.entity_mut(dice.entity);
+      match entity.get::<S>().copied().map(tick_status) {
+        Some(Some(ticked)) => { entity.insert(ticked); },
+        Some(None) => { entity.remove::<S>(); },
+        None => {},
+      }
+      *event = status.update_event(dice.id, *event);
+    }
+  }
+
+  /// Like the listener of `RegisterSynergy::register`: resolves the synergy of each team, then lets it change the event
+  fn trigger_synergy<S: Synergy>(&mut self, event: &mut dyn Any) {
+    let Some(event) = event.downcast_mut::<S::TriggerEvent>() else { return };
+    for team_id in 0..2 {
+      let Some(synergy) = self.team_synergy::<S>(team_id) else { continue };
+      if synergy.level() > 0 && synergy.trigger_condition(*event) {
+        if let Some(status) = synergy.granted_status() {
+          let targets = self.alive_where(|dice| synergy.grants_to(dice.id, &self.world.entity(dice.entity)));
+          for index in targets {
+            self.apply_status(index, &status);
+          }
+        }
+      }
+      *event = synergy.update_event(*event);
+    }
+  }
+
+  fn team_synergy<S: Synergy>(&self, team_id: usize) -> Option<S> {
+    let intensity = self.dices
+      .iter()
+      .filter(|dice| dice.id.team_id == team_id)
+      .flat_map(|dice| dice.faces)
+      .map(S::read_face)
+      .sum();
+    S::new(intensity, team_id)
+  }
+
+  fn roll_dices(&mut self) -> [Vec<(usize, FacePrototype)>; 2] {
+    let mut rows = [Vec::new(), Vec::new()];
+    for index in self.alive_where(|_| true) {
+      let face = self.dices[index].faces[self.rng.roll().gen_range(0..6)];
+      let dice_id = self.dices[index].id;
+      let FaceRolled { face, .. } = self.trigger(FaceRolled { dice_id, face });
+      self.log(BattleLogEntry::FaceRolled { dice_id, face });
+      rows[dice_id.team_id].push((index, face));
+    }
+    for row in &mut rows {
+      row.shuffle(self.rng.roll());
+    }
+    rows
+  }
+
+  fn resolve_dices(&mut self, rows: [Vec<(usize, FacePrototype)>; 2]) {
+    for i in 0..rows[0].len().max(rows[1].len()) {
+      for row in &rows {
+        if let Some(&(index, face)) = row.get(i) {
+          if !self.dices[index].alive() { continue }
+          let dice_id = self.dices[index].id;
+          let BeforeResolveDice { face, cancelled, .. } = self.trigger(BeforeResolveDice { dice_id, face, cancelled: false });
+          if cancelled { continue }
+          self.resolve_face(index, face);
+          self.trigger(AfterResolveDice { dice_id, face });
+        }
+      }
+    }
+  }
+
+  /// Same steps as `action::resolve`
+  fn resolve_face(&mut self, index: usize, face: FacePrototype) {
+    let dice_id = self.dices[index].id;
+    for _ in 0..repeats(face) {
+      let pips = face.pips.map(|pips| self.trigger(GetPips { dice_id, pips }).pips);
+      let Some((target, effect)) = action_rule(face.action, pips) else { continue };
+      let target = match target {
+        ActionTarget::Myself => Some(index),
+        ActionTarget::Ally => self.select_target(face, |dice| dice.id.team_id == dice_id.team_id && dice.id != dice_id),
+        ActionTarget::Enemy => self.select_target(face, |dice| dice.id.team_id != dice_id.team_id),
+      };
+      let Some(target) = target else { continue };
+
+      match effect {
+        ActionEffect::Damage(amount) => self.damage(Some(dice_id), target, amount),
+        ActionEffect::Status(status) => self.apply_status(target, &status),
+        ActionEffect::RemoveStatuses(kind) => {
+          let mut entity = self.world.entity_mut(self.dices[target].entity);
+          self.statuses.remove_kind(&mut entity, kind);
+        },
+      }
+    }
+  }
+
+  fn select_target(&mut self, face: FacePrototype, filter: impl Fn(&SimulatedDice) -> bool) -> Option<usize> {
+    let candidates = self.alive_where(filter)
+      .into_iter()
+      .map(|index| (self.dices[index].id, self.dices[index].health.current))
+      .collect();
+    let target = choose_target(candidates, face.background == FaceBackground::Cruel, self.rng.targeting())?;
+    self.dices.iter().position(|dice| dice.id == target)
+  }
+
+  fn alive_where(&self, filter: impl Fn(&SimulatedDice) -> bool) -> Vec<usize> {
+    self.dices
+      .iter()
+      .enumerate()
+      .filter(|(_, dice)| dice.alive() && filter(dice))
+      .map(|(index, _)| index)
+      .collect()
+  }
+
+  fn apply_status(&mut self, index: usize, status: &NewStatus) {
+    let dice_id = self.dices[index].id;
+    let applied = status.apply(&mut self.world.entity_mut(self.dices[index].entity));
+    if applied {
+      self.log(BattleLogEntry::StatusApplied { dice_id, status: status.name });
+    } else {
+      self.log(BattleLogEntry::StatusRefused { dice_id, status: status.name });
+    }
+  }
+
+  fn resolve_status_effect(&mut self, index: usize, effect: StatusEffect) {
+    match effect {
+      StatusEffect::Damage(amount) => self.damage(None, index, amount),
+      StatusEffect::Heal(amount) => self.heal(None, index, amount),
+    }
+  }
+
+  fn damage(&mut self, source: Option<DiceID>, index: usize, amount: u32) {
+    let target = self.dices[index].id;
+    let event = BeforeDamage { source, target, amount, color: Color::BLACK, cancelled: false };
+    let BeforeDamage { amount, cancelled, .. } = self.trigger(event);
+    if cancelled || amount == 0 { return }
+
+    let mut entity = self.world.entity_mut(self.dices[index].entity);
+    let amount = match entity.get::<Shield>().copied() {
+      Some(shield) => {
+        let (shield, amount) = absorb_damage(shield, amount);
+        match shield {
+          Some(shield) => { entity.insert(shield); },
+          None => { entity.remove::<Shield>(); },
+        }
+        amount
+      },
+      None => amount,
+    };
+    if amount == 0 { return }
+
+    let died = take_damage(&mut self.dices[index].health, amount);
+    self.log(BattleLogEntry::Damage { dice_id: target, amount });
+    self.trigger(AfterDamage { source, target, amount });
+    if died {
+      self.log(BattleLogEntry::DiceDied { dice_id: target });
+      self.trigger(DiceDied { dice_id: target });
+    }
+  }
+
+  fn heal(&mut self, source: Option<DiceID>, index: usize, amount: u32) {
+    let target = self.dices[index].id;
+    let BeforeHeal { amount, cancelled, .. } = self.trigger(BeforeHeal { source, target, amount, cancelled: false });
+    if cancelled || amount == 0 { return }
+    heal(&mut self.dices[index].health, amount);
+    self.log(BattleLogEntry::Heal { dice_id: target, amount });
+  }
+
+  fn winner(&self) -> Option<usize> {
+    let team_alive = |team_id| self.dices.iter().any(|dice| dice.alive() && dice.id.team_id == team_id);
+    if !team_alive(1) {
+      Some(0)
+    } else if !team_alive(0) {
+      Some(1)
+    } else {
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dice::{dice_class::{ClassHp, DiceClass}, face_prototypes::*, Action};
+
+  const EMPTY: FacePrototype = FacePrototype::new(Action::Empty, None);
+  const SEED: u64 = 42;
+  const ENEMY: DiceID = DiceID { team_id: 1, dice_id: 0 };
+
+  fn dice(faces: [FacePrototype; 6], hp: u32) -> DiceTemplateBuilder {
+    DiceTemplateBuilder::default().with_face_set(faces).with_hp(hp)
+  }
+
+  fn damage_taken(outcome: &BattleOutcome) -> Vec<(DiceID, u32)> {
+    outcome.rounds
+      .iter()
+      .flat_map(|round| &round.entries)
+      .filter_map(|entry| match *entry {
+        BattleLogEntry::Damage { dice_id, amount } => Some((dice_id, amount)),
+        _ => None,
+      })
+      .collect()
+  }
+
+  #[test]
+  fn same_seed_plays_the_same_battle() {
+    let team1 = || vec![dice([ATTACK_STRONG, FIRE_WEAK, REGEN_WEAK, BLOCK_WEAK, POISON_WEAK_CRUEL, FREEZE], 12); 3];
+    let team2 = || vec![dice([ATTACK_WEAK, DEFEND, FIERY, CLEANSE, ATTACK_STRONG_DOUBLE, FREEZE], 12); 3];
+    let log = |outcome: BattleOutcome| outcome.rounds
+      .iter()
+      .flat_map(|round| &round.entries)
+      .map(|entry| entry.to_string())
+      .collect::<Vec<_>>();
+
+    let first = BattleSimulator::new(team1(), team2(), SEED).run();
+    let second = BattleSimulator::new(team1(), team2(), SEED).run();
+    assert_eq!(first.winner, second.winner);
+    assert_eq!(log(first), log(second));
+  }
+
+  #[test]
+  fn warrior_boosts_attacks() {
+    // Six attack faces reach the second Warrior level, so every attack deals 2 + 2
+    let outcome = BattleSimulator::new([dice([ATTACK_STRONG; 6], 10)], [dice([EMPTY; 6], 5)], SEED).run();
+    assert_eq!(outcome.winner, Some(0));
+    assert_eq!(outcome.rounds.len(), 3);
+    assert_eq!(damage_taken(&outcome), [(ENEMY, 4), (ENEMY, 4)]);
+  }
+
+  #[test]
+  fn guardian_cancels_weak_attacks() {
+    // The third Guardian level takes 3 off the 1 + 2 pips of the attacks, neither team can win
+    let outcome = BattleSimulator::new([dice([ATTACK_WEAK; 6], 10)], [dice([DEFEND; 6], 5)], SEED).run();
+    assert_eq!(outcome.winner, None);
+    assert!(damage_taken(&outcome).is_empty());
+  }
+
+  #[test]
+  fn holy_shield_absorbs_the_first_round() {
+    // The 6 Shield of the third Holy level takes the first 4 damage and fades on the next round
+    let outcome = BattleSimulator::new([dice([ATTACK_STRONG; 6], 10)], [dice([REGEN_WEAK; 6], 5)], SEED).run();
+    assert_eq!(outcome.winner, Some(0));
+    assert_eq!(outcome.rounds.len(), 4);
+    assert_eq!(damage_taken(&outcome), [(ENEMY, 4), (ENEMY, 4)]);
+  }
+
+  #[test]
+  fn fiery_burning_counts_down() {
+    // Burning 3 from the third Fiery level deals 3, 2 then 1 at the start of the rounds
+    let outcome = BattleSimulator::new([dice([FIERY; 6], 10)], [dice([EMPTY; 6], 6)], SEED).run();
+    assert_eq!(outcome.winner, Some(0));
+    assert_eq!(outcome.rounds.len(), 4);
+    assert_eq!(damage_taken(&outcome), [(ENEMY, 3), (ENEMY, 2), (ENEMY, 1)]);
+  }
+
+  #[test]
+  fn cruel_attacks_pick_the_weakest() {
+    let weak = DiceID { team_id: 1, dice_id: 1 };
+    let outcome = BattleSimulator::new(
+      [dice([ATTACK_STRONG_CRUEL; 6], 10)],
+      [dice([EMPTY; 6], 10), dice([EMPTY; 6], 3)],
+      SEED,
+    ).run();
+    assert_eq!(outcome.winner, Some(0));
+    assert_eq!(damage_taken(&outcome)[0], (weak, 4));
+  }
+
+  #[test]
+  fn immune_dices_refuse_the_status() {
+    // The Burning of the Fiery synergy is refused at the start of the battle, so nothing ever deals damage
+    let class = DiceClass {
+      name: "salamander".to_string(),
+      hp: ClassHp { base: 6, per_level: 0 },
+      faces: vec![EMPTY; 6],
+      upgrades: Vec::new(),
+      immunities: vec!["Burning".to_string()],
+    };
+    let outcome = BattleSimulator::new([dice([FIERY; 6], 10)], [DiceTemplateBuilder::from_class(&class, 1)], SEED).run();
+    assert!(matches!(
+      outcome.rounds[0].entries[..],
+      [BattleLogEntry::StatusRefused { dice_id: ENEMY, status: "Burning" }],
+    ));
+    assert_eq!(outcome.winner, None);
+    assert!(damage_taken(&outcome).is_empty());
+  }
+}
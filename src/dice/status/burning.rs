@@ -1,38 +1,35 @@
 use bevy::prelude::*;
-use bevy_defer::{AccessError, AsyncWorld};
+use serde::{Deserialize, Serialize};
 
-use crate::{
-  battle::StartRound, dice::{
-    action::interaction::dice::damage, DiceID
-  }, impl_status_component
-};
+use crate::battle::{rules::StatusEffect, StartRound};
 
-use super::Status;
+use super::{count_down, Status, StatusKind};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Burning {
   pub intensity: u32,
 }
 
-impl_status_component!(Burning);
-
 impl Status for Burning {
   type TriggerEvent = StartRound;
   const STATUS_COLOR: Color = Color::linear_rgb(1.0, 0.0, 0.0);
+  const KIND: StatusKind = StatusKind::Debuff;
+  const ICON: &'static str = "actions/fire.png";
+
+  fn name() -> &'static str {
+    "Burning"
+  }
 
   fn description() -> &'static str {
     "Deals damage at the start of a turn and decreases intensity"
   }
 
-  async fn resolve_status(&self, dice_id: DiceID, _event: Self::TriggerEvent) -> Result<(), AccessError> {
-    damage(dice_id, self.intensity, Color::linear_rgb(1.0, 0.0, 0.0)).await?;
-    AsyncWorld.sleep(0.5).await;
-    Ok(())
+  fn effect(&self) -> Option<StatusEffect> {
+    Some(StatusEffect::Damage(self.intensity))
   }
   
   fn update(&mut self) -> bool {
-    self.intensity -= 1;
-    self.intensity == 0
+    count_down(&mut self.intensity)
   }
 
   fn combine(self, other: Self) -> Self {
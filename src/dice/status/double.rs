@@ -1,33 +1,33 @@
 use bevy::prelude::*;
-use bevy_defer::AccessError;
+use serde::{Deserialize, Serialize};
 
-use crate::{dice::{action::GetPips, Dice, DiceID}, impl_status_component};
+use crate::{dice::{action::GetPips, DiceID}};
 
-use super::Status;
+use super::{Status, StatusKind};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Double;
 
-impl_status_component!(Double);
-
 impl Status for Double {
   type TriggerEvent = GetPips;
   const STATUS_COLOR: Color = Color::linear_rgb(0.0, 0.0, 1.0);
+  const KIND: StatusKind = StatusKind::Buff;
+  const ICON: &'static str = "actions/potion_blue.png";
 
-  fn description() -> &'static str {
-    "Double the pips on next actions"
+  fn name() -> &'static str {
+    "Double"
   }
 
-  fn trigger_condition(&self, dice: &Dice, event: GetPips) -> bool {
-    event.dice_id == dice.id()
+  fn description() -> &'static str {
+    "Double the pips on next actions"
   }
 
-  async fn resolve_status(&self, _dice_id: DiceID, _event: GetPips) -> Result<(), AccessError> {
-    Ok(())
+  fn trigger_condition(&self, dice_id: DiceID, event: GetPips) -> bool {
+    event.dice_id == dice_id
   }
 
-  async fn update_event(&self, _dice_id: DiceID, event: Self::TriggerEvent) -> Result<Self::TriggerEvent, AccessError> {
-    Ok(GetPips { pips: event.pips * 2, ..event })
+  fn update_event(&self, _dice_id: DiceID, event: Self::TriggerEvent) -> Self::TriggerEvent {
+    GetPips { pips: event.pips * 2, ..event }
   }
   
   fn update(&mut self) -> bool {
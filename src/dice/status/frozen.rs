@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+use bevy_defer::{fetch, AccessError, AsyncAccess, AsyncWorld};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  battle::SpawnFloatingText, dice::{
+    animation::get_dice_entity, events::BeforeResolveDice, DiceID
+  }
+};
+
+use super::{count_down, Status, StatusKind};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Frozen {
+  pub turns: u32,
+}
+
+impl Status for Frozen {
+  type TriggerEvent = BeforeResolveDice;
+  const STATUS_COLOR: Color = Color::linear_rgb(0.5, 0.8, 1.0);
+  const KIND: StatusKind = StatusKind::Debuff;
+  const ICON: &'static str = "actions/frost.png";
+
+  fn name() -> &'static str {
+    "Frozen"
+  }
+
+  fn description() -> &'static str {
+    "Skips the face resolution for a number of turns"
+  }
+
+  fn trigger_condition(&self, dice_id: DiceID, event: BeforeResolveDice) -> bool {
+    event.dice_id == dice_id
+  }
+
+  async fn resolve_status(&self, dice_id: DiceID, _event: Self::TriggerEvent) -> Result<(), AccessError> {
+    let entity = get_dice_entity(dice_id).await?;
+    let position = fetch!(entity, Transform).get(|t| t.translation)?;
+    AsyncWorld.send_event(
+      SpawnFloatingText::new("Frozen".to_string(), position)
+        .with_color(Self::STATUS_COLOR)
+    )?;
+    AsyncWorld.sleep(0.5).await;
+    Ok(())
+  }
+
+  fn update_event(&self, _dice_id: DiceID, event: Self::TriggerEvent) -> Self::TriggerEvent {
+    BeforeResolveDice { cancelled: true, ..event }
+  }
+
+  fn update(&mut self) -> bool {
+    count_down(&mut self.turns)
+  }
+
+  fn combine(self, other: Self) -> Self {
+    Self {
+      turns: self.turns + other.turns,
+    }
+  }
+
+  fn intensity(&self) -> Option<u32> {
+    None
+  }
+
+  fn duration(&self) -> Option<u32> {
+    Some(self.turns)
+  }
+}
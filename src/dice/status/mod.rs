@@ -1,38 +1,64 @@
-use std::{fmt::Debug, sync::Arc};
+use std::{fmt::Debug, marker::PhantomData};
 
 use bevy::{ecs::component::Mutable, prelude::*};
-use bevy_defer::{AccessError, AsyncAccess, AsyncWorld};
+use bevy_defer::AccessError;
+use serde::{de::DeserializeOwned, Serialize};
 
-use crate::utils::*;
+use crate::battle::rules::StatusEffect;
 
-use super::{Dice, DiceID};
+use super::DiceID;
 
 mod burning;
+mod frozen;
 mod plugin;
 mod double;
 mod regeneration;
-
+mod registry;
+mod poison;
+mod shield;
 
 pub use burning::Burning;
 pub use double::Double;
-pub use plugin::StatusPlugin;
+pub use frozen::Frozen;
+pub use plugin::{register_statuses, StatusPlugin};
+pub use poison::Poison;
 pub use regeneration::Regeneration;
+pub use registry::{RegisterStatus, SavedStatus, StatusInfo, StatusRegistry};
+pub use shield::Shield;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusKind {
+  Buff,
+  Debuff,
+}
 
-pub trait Status: Component<Mutability=Mutable> + Clone + Copy {
+pub trait Status: Component<Mutability=Mutable> + Clone + Copy + Serialize + DeserializeOwned {
   type TriggerEvent: Event + Clone + Copy + Debug;
 
   const STATUS_COLOR: Color;
+  const KIND: StatusKind;
+  /// Image shown in the status bar, loaded by the `AssetStore`
+  const ICON: &'static str;
 
+  fn name() -> &'static str;
   fn description() -> &'static str;
 
-  fn trigger_condition(&self, _dice: &Dice, _event: Self::TriggerEvent) -> bool {
+  fn trigger_condition(&self, _dice_id: DiceID, _event: Self::TriggerEvent) -> bool {
     true
   }
 
-  async fn resolve_status(&self, dice_id: DiceID, event: Self::TriggerEvent) -> Result<(), AccessError>;
+  /// What the status does to its dice when it triggers, applied by the status listener and the simulator
+  fn effect(&self) -> Option<StatusEffect> {
+    None
+  }
 
-  async fn update_event(&self, _dice_id: DiceID, event: Self::TriggerEvent) -> Result<Self::TriggerEvent, AccessError> {
-    Ok(event)
+  /// Feedback shown once the `effect` is applied
+  async fn resolve_status(&self, _dice_id: DiceID, _event: Self::TriggerEvent) -> Result<(), AccessError> {
+    Ok(())
+  }
+
+  fn update_event(&self, _dice_id: DiceID, event: Self::TriggerEvent) -> Self::TriggerEvent {
+    event
   }
   
   fn update(&mut self) -> bool;
@@ -40,106 +66,25 @@ pub trait Status: Component<Mutability=Mutable> + Clone + Copy {
   fn combine(self, other: Self) -> Self;
 
   fn intensity(&self) -> Option<u32>;
-}
 
-#[macro_export]
-macro_rules! impl_status_component {
-  ($t:ty) => {
-    use bevy::{ecs::component::{ComponentHook, Mutable, StorageType}};
-    use $crate::dice::dice_info_bar::{StatusBar, StatusIconOf, StatusIcon, StatusIntensityOf};
-    use $crate::constants::dice_info_bar::{STATUS_ICON_SIZE, STATUS_TEXT_SIZE, STATUS_MARGIN};
-    use $crate::utils::tooltip::Tooltip;
-
-    impl Component for $t {
-      const STORAGE_TYPE: StorageType = StorageType::Table;
-      type Mutability = Mutable;
-
-      fn on_add() -> Option<ComponentHook> {
-        Some(|mut world, context| {
-          let bar = world
-            .get::<StatusBar>(context.entity).unwrap().bar();
-          world
-            .commands()
-            .entity(bar)
-            .with_child((
-              Name::new("Status Icon"),
-              StatusIconOf::<Self>::new(context.entity),
-              Node {
-                width: Val::Px(STATUS_ICON_SIZE.x),
-                height: Val::Px(STATUS_ICON_SIZE.y),
-                margin: UiRect::all(Val::Px(STATUS_MARGIN)),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-              },
-              BackgroundColor(<$t>::STATUS_COLOR),
-              related!(Children[(
-                Text::new(""),
-                TextFont {
-                  font_size: STATUS_TEXT_SIZE,
-                  ..default()
-                },
-                TextColor::BLACK,
-                StatusIntensityOf::<Self>::new(context.entity),
-                Pickable::IGNORE,
-              )]),
-              related!(Tooltip[(
-                Name::new("Status Tooltip"),
-                Text::new(Self::description()),
-              )]),
-            ));
-        })
-      }
-
-      fn on_remove() -> Option<ComponentHook> {
-        Some(|mut world, context| {
-          let icon = world
-            .get::<StatusIcon::<Self>>(context.entity).unwrap().icon();
-          world
-            .commands()
-            .entity(icon)
-            .despawn();
-        })
-      }
-    }
-  };
+  /// Turns left before the status wears off, for statuses that count them
+  fn duration(&self) -> Option<u32> {
+    None
+  }
 }
 
-pub trait RegisterStatus {
-  fn register<S: Status>(&mut self) -> &mut Self;
+/// Decrements the counter of a status, returns true once it ran out
+pub fn count_down(counter: &mut u32) -> bool {
+  *counter = counter.saturating_sub(1);
+  *counter == 0
 }
 
-impl RegisterStatus for App {
-  fn register<S>(&mut self) -> &mut Self
-  where
-    S: Status,
-  {
-    let listener: DynAsyncFunction<S::TriggerEvent> = Arc::new(move |event| {
-      Box::pin(async move {
-        let mut dice_ids = Vec::new();
-        AsyncWorld
-          .query::<(Entity, &Dice, &S)>()
-          .for_each(|(entity, dice, status)| {
-            if status.trigger_condition(dice, event.get()) {
-              dice_ids.push((entity, dice.id(), *status));
-            }
-          });
-        let statuses = AsyncWorld
-          .query::<&mut S>();
-        let mut new_event = event.get();
-
-        for (entity, dice_id, status) in dice_ids {
-          status.resolve_status(dice_id, event.get()).await?;
-          if Ok(true) == statuses.entity(entity).get_mut(|mut status| status.update()) {
-            AsyncWorld.entity(entity).component::<S>().remove();
-          }
-          new_event = status.update_event(dice_id, new_event).await?;
-        }
-        event.mutate(|_| new_event);
-        Ok(())
-      })
-    });
-    self.register_dyn_listener(listener);
-    self
+/// A dice with this component refuses the status `S`
+#[derive(Component)]
+pub struct Immune<S: Status>(PhantomData<S>);
+
+impl<S: Status> Default for Immune<S> {
+  fn default() -> Self {
+    Self(PhantomData)
   }
 }
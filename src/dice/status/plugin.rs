@@ -2,15 +2,23 @@ use bevy::prelude::*;
 
 use crate::dice::status::RegisterStatus;
 
-use super::{Burning, Double, Regeneration};
+use super::{Burning, Double, Frozen, Poison, Regeneration, Shield};
 
 pub struct StatusPlugin;
 
 impl Plugin for StatusPlugin {
   fn build(&self, app: &mut App) {
-    app
-      .register::<Burning>()
-      .register::<Double>()
-      .register::<Regeneration>();
+    register_statuses(app);
   }
 }
+
+/// Every status of the game, in the order their listeners run
+pub fn register_statuses(registrar: &mut impl RegisterStatus) {
+  registrar
+    .register_status::<Burning>()
+    .register_status::<Double>()
+    .register_status::<Regeneration>()
+    .register_status::<Shield>()
+    .register_status::<Poison>()
+    .register_status::<Frozen>();
+}
@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::battle::{rules::StatusEffect, EndRound};
+
+use super::{Status, StatusKind};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Poison {
+  pub stacks: u32,
+}
+
+impl Status for Poison {
+  type TriggerEvent = EndRound;
+  const STATUS_COLOR: Color = Color::linear_rgb(0.5, 0.0, 0.8);
+  const KIND: StatusKind = StatusKind::Debuff;
+  const ICON: &'static str = "actions/drop_poison.png";
+
+  fn name() -> &'static str {
+    "Poison"
+  }
+
+  fn description() -> &'static str {
+    "Deals damage equal to stacks at the end of a turn, does not wear off"
+  }
+
+  fn effect(&self) -> Option<StatusEffect> {
+    Some(StatusEffect::Damage(self.stacks))
+  }
+
+  fn update(&mut self) -> bool {
+    false
+  }
+
+  fn combine(self, other: Self) -> Self {
+    Self {
+      stacks: self.stacks + other.stacks,
+    }
+  }
+
+  fn intensity(&self) -> Option<u32> {
+    Some(self.stacks)
+  }
+}
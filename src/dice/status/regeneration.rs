@@ -1,39 +1,42 @@
 use bevy::prelude::*;
-use bevy_defer::{AccessError, AsyncWorld};
+use serde::{Deserialize, Serialize};
 
-use crate::{
-  battle::StartRound, dice::{
-    action::interaction::dice::heal, DiceID
-  }, impl_status_component
-};
+use crate::battle::{rules::StatusEffect, StartRound};
 
-use super::Status;
+use super::{count_down, Status, StatusKind};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Regeneration {
   pub heal_amount: u32,
   pub duration_left: u32,
 }
 
-impl_status_component!(Regeneration);
+impl Regeneration {
+  pub fn new(heal_amount: u32) -> Self {
+    Self { heal_amount, duration_left: 3 }
+  }
+}
 
 impl Status for Regeneration {
   type TriggerEvent = StartRound;
   const STATUS_COLOR: Color = Color::linear_rgb(0.0, 1.0, 0.0);
+  const KIND: StatusKind = StatusKind::Buff;
+  const ICON: &'static str = "actions/heart.png";
+
+  fn name() -> &'static str {
+    "Regeneration"
+  }
 
   fn description() -> &'static str {
     "Regenerates health at the start of turn a 3 times"
   }
 
-  async fn resolve_status(&self, dice_id: DiceID, _event: Self::TriggerEvent) -> Result<(), AccessError> {
-    heal(dice_id, self.heal_amount).await?;
-    AsyncWorld.sleep(0.5).await;
-    Ok(())
+  fn effect(&self) -> Option<StatusEffect> {
+    Some(StatusEffect::Heal(self.heal_amount))
   }
 
   fn update(&mut self) -> bool {
-    self.duration_left -= 1;
-    self.duration_left == 0
+    count_down(&mut self.duration_left)
   }
 
   fn combine(self, other: Self) -> Self {
@@ -46,4 +49,8 @@ impl Status for Regeneration {
   fn intensity(&self) -> Option<u32> {
     Some(self.heal_amount)
   }
+
+  fn duration(&self) -> Option<u32> {
+    Some(self.duration_left)
+  }
 }
@@ -0,0 +1,259 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy_defer::{fetch, AsyncAccess, AsyncWorld};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  constants::dice_info_bar::{STATUS_BORDER, STATUS_ICON_SIZE, STATUS_MARGIN, STATUS_TEXT_SIZE},
+  battle::rules::tick_status,
+  dice::{
+    action::interaction::dice::resolve_status_effect,
+    dice_info_bar::{update_status_overlays, StatusBar, StatusDurationOf, StatusIcon, StatusIconOf, StatusIntensityOf, StatusPulse},
+    Dice,
+  },
+  loading_screen::AssetStore,
+  utils::{ron_asset::RonError, tooltip::Tooltip, *},
+};
+
+use super::{Immune, Status, StatusKind};
+
+#[derive(Clone, Copy, Debug)]
+pub struct StatusInfo {
+  pub name: &'static str,
+  pub kind: StatusKind,
+  pub icon: &'static str,
+}
+
+/// A status written as RON, keyed by the name it was registered with
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedStatus {
+  pub name: String,
+  pub ron: String,
+}
+
+/// Type erased access to a registered status
+#[derive(Clone, Copy)]
+struct StatusEntry {
+  info: StatusInfo,
+  has: fn(&EntityRef) -> bool,
+  remove: fn(&mut EntityWorldMut),
+  serialize: fn(&EntityRef) -> Option<String>,
+  deserialize: fn(&mut EntityWorldMut, &str) -> Result<(), RonError>,
+  make_immune: fn(&mut EntityWorldMut),
+}
+
+/// Every status known to the game, filled by `register_status`
+#[derive(Resource, Default, Clone)]
+pub struct StatusRegistry {
+  entries: Vec<StatusEntry>,
+}
+
+impl StatusRegistry {
+  fn add<S: Status>(&mut self) {
+    self.entries.push(StatusEntry {
+      info: StatusInfo { name: S::name(), kind: S::KIND, icon: S::ICON },
+      has: |entity| entity.contains::<S>(),
+      remove: |entity| { entity.remove::<S>(); },
+      serialize: |entity| entity
+        .get::<S>()
+        .and_then(|status| ron::to_string(status).ok()),
+      deserialize: |entity, ron| {
+        entity.insert(ron::from_str::<S>(ron)?);
+        Ok(())
+      },
+      make_immune: |entity| { entity.insert(Immune::<S>::default()); },
+    });
+  }
+
+  pub fn known_statuses(&self) -> impl Iterator<Item = StatusInfo> + '_ {
+    self.entries.iter().map(|entry| entry.info)
+  }
+
+  /// Lists the statuses currently on the entity
+  pub fn statuses_of(&self, entity: &EntityRef) -> Vec<StatusInfo> {
+    self.entries
+      .iter()
+      .filter(|entry| (entry.has)(entity))
+      .map(|entry| entry.info)
+      .collect()
+  }
+
+  /// Every status on the entity written as RON, keyed by its name
+  pub fn serialize_statuses_of(&self, entity: &EntityRef) -> Vec<SavedStatus> {
+    self.entries
+      .iter()
+      .filter_map(|entry| Some(SavedStatus {
+        name: entry.info.name.to_string(),
+        ron: (entry.serialize)(entity)?,
+      }))
+      .collect()
+  }
+
+  /// Reads back a status written by `serialize_statuses_of` and inserts it on the entity,
+  /// replacing the status of the same type if there is one
+  pub fn deserialize_status(&self, entity: &mut EntityWorldMut, status: &SavedStatus) -> Result<(), RonError> {
+    (self.entry(&status.name)?.deserialize)(entity, &status.ron)
+  }
+
+  /// Makes the entity refuse the status with this name from now on
+  pub fn make_immune(&self, entity: &mut EntityWorldMut, name: &str) -> Result<(), RonError> {
+    (self.entry(name)?.make_immune)(entity);
+    Ok(())
+  }
+
+  fn entry(&self, name: &str) -> Result<&StatusEntry, RonError> {
+    self.entries
+      .iter()
+      .find(|entry| entry.info.name == name)
+      .ok_or_else(|| format!("Unknown status {name}").into())
+  }
+
+  /// Removes every status of the given kind from the entity
+  pub fn remove_kind(&self, entity: &mut EntityWorldMut, kind: StatusKind) {
+    for entry in &self.entries {
+      if entry.info.kind == kind {
+        (entry.remove)(entity);
+      }
+    }
+  }
+}
+
+pub trait RegisterStatus {
+  /// Wires everything a status needs: its trigger listener, the icon in the status bar
+  /// with its tooltip and intensity, and its entry in the `StatusRegistry`
+  fn register_status<S: Status>(&mut self) -> &mut Self;
+}
+
+impl RegisterStatus for App {
+  fn register_status<S: Status>(&mut self) -> &mut Self {
+    let listener: DynAsyncFunction<S::TriggerEvent> = Arc::new(move |event| {
+      Box::pin(async move {
+        let mut dice_ids = Vec::new();
+        // `Immune` only refuses new applications, a status already there keeps ticking down
+        AsyncWorld
+          .query::<(Entity, &Dice, &S)>()
+          .for_each(|(entity, dice, status)| {
+            if status.trigger_condition(dice.id(), event.get()) {
+              dice_ids.push((entity, dice.id(), *status));
+            }
+          });
+        let statuses = AsyncWorld
+          .query::<&mut S>();
+        let mut new_event = event.get();
+
+        for (entity, dice_id, status) in dice_ids {
+          if let Ok(icon) = fetch!(entity, StatusIcon<S>).get(|icon| icon.icon()) {
+            let _ = AsyncWorld.entity(icon).insert(StatusPulse::default());
+          }
+          if let Some(effect) = status.effect() {
+            resolve_status_effect(dice_id, effect, S::STATUS_COLOR).await?;
+          }
+          status.resolve_status(dice_id, event.get()).await?;
+          let worn_off = statuses
+            .entity(entity)
+            .get_mut(|mut status| match tick_status(*status) {
+              Some(ticked) => { *status = ticked; false },
+              None => true,
+            });
+          if Ok(true) == worn_off {
+            AsyncWorld.entity(entity).component::<S>().remove();
+          }
+          new_event = status.update_event(dice_id, new_event);
+        }
+        event.mutate(|_| new_event);
+        Ok(())
+      })
+    });
+
+    self
+      .register_dyn_listener(listener)
+      .add_observer(spawn_status_icon::<S>)
+      .add_observer(despawn_status_icon::<S>)
+      .add_systems(Update, update_status_overlays::<S>)
+      .world_mut()
+      .get_resource_or_init::<StatusRegistry>()
+      .register_status::<S>();
+    self
+  }
+}
+
+impl RegisterStatus for StatusRegistry {
+  fn register_status<S: Status>(&mut self) -> &mut Self {
+    self.add::<S>();
+    self
+  }
+}
+
+fn spawn_status_icon<S: Status>(
+  trigger: Trigger<OnAdd, S>,
+  mut commands: Commands,
+  status_bars: Query<&StatusBar>,
+  asset_store: Res<AssetStore>,
+) {
+  let dice = trigger.target();
+  let Ok(status_bar) = status_bars.get(dice) else { return };
+  commands
+    .entity(status_bar.bar())
+    .with_child((
+      Name::new("Status Icon"),
+      StatusIconOf::<S>::new(dice),
+      Node {
+        width: Val::Px(STATUS_ICON_SIZE.x),
+        height: Val::Px(STATUS_ICON_SIZE.y),
+        margin: UiRect::all(Val::Px(STATUS_MARGIN)),
+        border: UiRect::all(Val::Px(STATUS_BORDER)),
+        ..default()
+      },
+      ImageNode::new(asset_store.get(S::ICON)),
+      BorderColor(S::STATUS_COLOR),
+      related!(Children[
+        (
+          Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(0.0),
+            bottom: Val::Px(0.0),
+            ..default()
+          },
+          Text::new(""),
+          TextFont {
+            font_size: STATUS_TEXT_SIZE,
+            ..default()
+          },
+          TextColor::WHITE,
+          StatusIntensityOf::<S>::new(dice),
+          Pickable::IGNORE,
+        ),
+        (
+          Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            top: Val::Px(0.0),
+            ..default()
+          },
+          Text::new(""),
+          TextFont {
+            font_size: STATUS_TEXT_SIZE,
+            ..default()
+          },
+          TextColor(S::STATUS_COLOR),
+          StatusDurationOf::<S>::new(dice),
+          Pickable::IGNORE,
+        ),
+      ]),
+      related!(Tooltip[(
+        Name::new("Status Tooltip"),
+        Text::new(S::description()),
+      )]),
+    ));
+}
+
+fn despawn_status_icon<S: Status>(
+  trigger: Trigger<OnRemove, S>,
+  mut commands: Commands,
+  icons: Query<&StatusIcon<S>>,
+) {
+  if let Ok(icon) = icons.get(trigger.target()) {
+    commands.entity(icon.icon()).try_despawn();
+  }
+}
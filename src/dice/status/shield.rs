@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::battle::StartRound;
+
+use super::{Status, StatusKind};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shield {
+  pub amount: u32,
+}
+
+impl Status for Shield {
+  type TriggerEvent = StartRound;
+  const STATUS_COLOR: Color = Color::linear_rgb(0.6, 0.6, 0.6);
+  const KIND: StatusKind = StatusKind::Buff;
+  const ICON: &'static str = "actions/shield.png";
+
+  fn name() -> &'static str {
+    "Shield"
+  }
+
+  fn description() -> &'static str {
+    "Absorbs incoming damage until the start of the next turn"
+  }
+
+  fn update(&mut self) -> bool {
+    true
+  }
+
+  fn combine(self, other: Self) -> Self {
+    Self {
+      amount: self.amount + other.amount,
+    }
+  }
+
+  fn intensity(&self) -> Option<u32> {
+    Some(self.amount)
+  }
+}
@@ -1,26 +1,16 @@
 use bevy::prelude::*;
-use bevy_defer::{AccessError, AsyncWorld};
 
-use crate::{battle::StartGame, dice::{action::interaction::dice::apply_status, status::Burning, synergy::Synergy, Action, Dice, FacePrototype}};
+use crate::{battle::{rules::NewStatus, StartGame}, dice::{status::Burning, synergy::{ActionCount, Synergy}, Action, DiceID}};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct Fiery {
-  intensity: u32,
-  team_id: usize,
-}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deref)]
+pub struct Fiery(ActionCount);
 
 impl Synergy for Fiery {
   type TriggerEvent = StartGame;
 
   const SYNERGY_COLOR: Color = Color::linear_rgb(1.0, 0.5, 0.5);
-
-  fn new(intensity: u32, team_id: usize) -> Option<Self> {
-    if intensity > 0 {
-      Some(Self { intensity, team_id })
-    } else {
-      None
-    }
-  }
+  const ACTION: Action = Action::Fiery;
+  const FROM_COUNT: fn(ActionCount) -> Self = Self;
 
   fn name() -> &'static str {
     "Fiery"
@@ -34,32 +24,15 @@ impl Synergy for Fiery {
     "
   }
 
-  async fn resolve(&self, _event: Self::TriggerEvent) -> Result<(), AccessError> {
-    let mut dices = Vec::new();
-
-    AsyncWorld
-      .query::<&Dice>()
-      .for_each(|dice| {
-        if dice.id().team_id != self.team_id {
-          dices.push(dice.id());
-        }
-      });
-
-    for dice_id in dices {
-      apply_status(dice_id, Burning { intensity: self.level() }).await?;
-    }
-    Ok(())
+  fn granted_status(&self) -> Option<NewStatus> {
+    Some(NewStatus::new(Burning { intensity: self.level() }))
   }
 
-  fn intensity(&self) -> u32 {
-    self.intensity
+  fn grants_to(&self, dice_id: DiceID, _entity: &EntityRef) -> bool {
+    dice_id.team_id != self.team_id
   }
 
   fn break_points(&self) -> &[u32] {
     &[1, 3, 5]
   }
-
-  fn read_face(face: FacePrototype) -> u32 {
-    if face.action == Action::Fiery { 1 } else { 0 }
-  }
-} 
+}
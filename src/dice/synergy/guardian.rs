@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+
+use crate::dice::{events::BeforeDamage, synergy::{ActionCount, Synergy}, Action};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deref)]
+pub struct Guardian(ActionCount);
+
+impl Synergy for Guardian {
+  type TriggerEvent = BeforeDamage;
+
+  const SYNERGY_COLOR: Color = Color::linear_rgb(0.5, 0.7, 1.0);
+  const ACTION: Action = Action::Defend;
+  const FROM_COUNT: fn(ActionCount) -> Self = Self;
+
+  fn name() -> &'static str {
+    "Guardian"
+  }
+
+  fn description() -> &'static str {
+    "Allies take less damage from attacks
+    2 -> -1 damage
+    4 -> -2 damage
+    6 -> -3 damage
+    "
+  }
+
+  fn trigger_condition(&self, event: Self::TriggerEvent) -> bool {
+    event.source.is_some() && event.target.team_id == self.team_id && self.reduce(event.amount) != event.amount
+  }
+
+  fn update_event(&self, mut event: Self::TriggerEvent) -> Self::TriggerEvent {
+    // Damage from statuses has no source and goes through
+    if self.trigger_condition(event) {
+      event.amount = self.reduce(event.amount);
+    }
+    event
+  }
+
+  fn break_points(&self) -> &[u32] {
+    &[2, 4, 6]
+  }
+}
+
+impl Guardian {
+  pub fn reduce(&self, amount: u32) -> u32 {
+    amount.saturating_sub(self.level())
+  }
+}
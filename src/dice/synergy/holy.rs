@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+
+use crate::{battle::{rules::NewStatus, BeforeRollDices}, dice::{status::Shield, synergy::{ActionCount, Synergy}, Action, DiceID}};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deref)]
+pub struct Holy(ActionCount);
+
+impl Synergy for Holy {
+  // Shields fade on `StartRound`, so they are granted once that is over, whatever the listener order
+  type TriggerEvent = BeforeRollDices;
+
+  const SYNERGY_COLOR: Color = Color::linear_rgb(1.0, 1.0, 0.6);
+  const ACTION: Action = Action::Regenerate;
+  const FROM_COUNT: fn(ActionCount) -> Self = Self;
+
+  fn name() -> &'static str {
+    "Holy"
+  }
+
+  fn description() -> &'static str {
+    "Shields all allies at the start of battle
+    2 -> 2 Shield
+    4 -> 4 Shield
+    6 -> 6 Shield
+    "
+  }
+
+  fn trigger_condition(&self, event: Self::TriggerEvent) -> bool {
+    event.round == 1 && self.level() > 0
+  }
+
+  fn granted_status(&self) -> Option<NewStatus> {
+    Some(NewStatus::new(Shield { amount: 2 * self.level() }))
+  }
+
+  fn grants_to(&self, dice_id: DiceID, _entity: &EntityRef) -> bool {
+    dice_id.team_id == self.team_id
+  }
+
+  fn break_points(&self) -> &[u32] {
+    &[2, 4, 6]
+  }
+}
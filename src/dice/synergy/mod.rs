@@ -1,23 +1,46 @@
-use std::sync::Arc;
+use std::{ops::Deref, sync::Arc};
 
-use bevy::prelude::*;
+use bevy::{ecs::relationship::RelatedSpawnerCommands, prelude::*};
 use bevy_defer::{AccessError, AsyncAccess, AsyncWorld};
 use std::fmt::Debug;
 
 mod fiery;
+mod guardian;
+mod holy;
+mod venomous;
+mod warrior;
 mod plugin;
 
 pub use fiery::Fiery;
-pub use plugin::{SynergyPlugin, spawn_synergy_displays};
+pub use guardian::Guardian;
+pub use holy::Holy;
+pub use venomous::Venomous;
+pub use warrior::Warrior;
+pub use plugin::{register_synergies, SynergyPlugin, spawn_synergy_displays};
 
-use crate::{dice::FacePrototype, utils::{ArcMutexMutable, DynAsyncFunction, RegisterListener}};
+use plugin::{highlight_synergy, spawn_synergy_display, update_synergy_display, update_team_synergy};
 
-pub trait Synergy: Clone + Copy + Send + Sync + 'static {
+use crate::{battle::rules::NewStatus, dice::{action::interaction::dice::apply_status, Action, Dice, DiceID, FacePrototype}, utils::{ArcMutexMutable, DynAsyncFunction, RegisterListener}};
+
+/// Number of faces of a team with the action of a synergy, shared by every synergy through `Deref`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActionCount {
+  pub intensity: u32,
+  pub team_id: usize,
+}
+
+pub trait Synergy: Clone + Copy + Send + Sync + Deref<Target = ActionCount> + 'static {
   type TriggerEvent: Event + Clone + Copy + Debug;
 
   const SYNERGY_COLOR: Color;
+  /// Faces with this action make the intensity of the synergy
+  const ACTION: Action;
+  /// Wraps the count into the synergy, the tuple struct constructor
+  const FROM_COUNT: fn(ActionCount) -> Self;
 
-  fn new(intensity: u32, team_id: usize) -> Option<Self>;
+  fn new(intensity: u32, team_id: usize) -> Option<Self> {
+    (intensity > 0).then(|| Self::FROM_COUNT(ActionCount { intensity, team_id }))
+  }
 
   fn name() -> &'static str;
   fn description() -> &'static str;
@@ -26,16 +49,41 @@ pub trait Synergy: Clone + Copy + Send + Sync + 'static {
     true
   }
 
-  async fn resolve(&self, event: Self::TriggerEvent) -> Result<(), AccessError>;
+  /// Status applied when the synergy resolves, to every dice `grants_to` accepts
+  fn granted_status(&self) -> Option<NewStatus> {
+    None
+  }
 
-  async fn update_event(&self, event: Self::TriggerEvent) -> Result<Self::TriggerEvent, AccessError> {
-    Ok(event)
+  fn grants_to(&self, _dice_id: DiceID, _entity: &EntityRef) -> bool {
+    false
+  }
+
+  async fn resolve(&self, _event: Self::TriggerEvent) -> Result<(), AccessError> {
+    let Some(status) = self.granted_status() else { return Ok(()) };
+    let dice_ids = AsyncWorld.run(|world| {
+      let mut dices = world.query::<(EntityRef, &Dice)>();
+      dices
+        .iter(world)
+        .filter(|(entity, dice)| self.grants_to(dice.id(), entity))
+        .map(|(_, dice)| dice.id())
+        .collect::<Vec<_>>()
+    });
+    for dice_id in dice_ids {
+      apply_status(dice_id, status.clone()).await?;
+    }
+    Ok(())
+  }
+
+  fn update_event(&self, event: Self::TriggerEvent) -> Self::TriggerEvent {
+    event
   }
   
   #[allow(unused)]
   fn update(&mut self) {}
 
-  fn intensity(&self) -> u32;
+  fn intensity(&self) -> u32 {
+    self.intensity
+  }
 
   fn break_points(&self) -> &[u32];
 
@@ -48,7 +96,9 @@ pub trait Synergy: Clone + Copy + Send + Sync + 'static {
     self.break_points().len() as u32
   }
 
-  fn read_face(face: FacePrototype) -> u32;
+  fn read_face(face: FacePrototype) -> u32 {
+    if face.action == Self::ACTION { 1 } else { 0 }
+  }
 }
 
 #[derive(Resource, Clone, Copy, Debug)]
@@ -70,6 +120,12 @@ async fn get_team_synergy<S: Synergy>(team_id: usize) -> Result<Option<S>, Acces
     .get(|synergies| synergies.synergies[team_id])
 }
 
+/// Every synergy known to the game, filled by `register`
+#[derive(Resource, Default)]
+pub struct SynergyRegistry {
+  displays: Vec<fn(&mut RelatedSpawnerCommands<ChildOf>, usize)>,
+}
+
 pub trait RegisterSynergy {
   fn register<S: Synergy>(&mut self) -> &mut Self;
 }
@@ -80,10 +136,12 @@ impl RegisterSynergy for App {
       Box::pin(async move {
         for team_id in 0..2 {
           if let Some(synergy) = get_team_synergy::<S>(team_id).await? {
-            if synergy.trigger_condition(event.get()) {
+            // Highlighted only when it actually does something
+            if synergy.level() > 0 && synergy.trigger_condition(event.get()) {
               synergy.resolve(event.get()).await?;
+              AsyncWorld.run_system_cached_with(highlight_synergy::<S>, team_id)?;
             }
-            let new_event = synergy.update_event(event.get()).await?;
+            let new_event = synergy.update_event(event.get());
             event.mutate(|_| new_event);
           }
         }
@@ -91,7 +149,15 @@ impl RegisterSynergy for App {
       })
     });
 
-    self.register_dyn_listener(listener);
+    self
+      .init_resource::<TeamSynergy<S>>()
+      .register_dyn_listener(listener)
+      .add_systems(Update, (update_team_synergy::<S>, update_synergy_display::<S>));
+    self
+      .world_mut()
+      .resource_mut::<SynergyRegistry>()
+      .displays
+      .push(spawn_synergy_display::<S>);
     self
   }
 }
@@ -2,30 +2,64 @@ use std::marker::PhantomData;
 
 use bevy::{ecs::relationship::RelatedSpawnerCommands, prelude::*};
 
-use crate::{dice::{synergy::{RegisterSynergy, Synergy, TeamSynergy}, Face}, manage::plugin::{EnemyTeam, MyTeam}, utils::tooltip::Tooltip};
+use crate::{constants::ui::SYNERGY_HIGHLIGHT_DURATION, dice::{synergy::{RegisterSynergy, Synergy, SynergyRegistry, TeamSynergy}, Face}, manage::plugin::{EnemyTeam, MyTeam}, states::InBattle, utils::tooltip::Tooltip};
 
-use super::Fiery;
+use super::{Fiery, Guardian, Holy, Venomous, Warrior};
 
 pub struct SynergyPlugin;
 
 impl Plugin for SynergyPlugin {
   fn build(&self, app: &mut App) {
+    app.init_resource::<SynergyRegistry>();
+    register_synergies(app);
     app
-      .init_resource::<TeamSynergy<Fiery>>()
-      .register::<Fiery>()
-      .add_systems(Update, (update_team_synergy::<Fiery>, update_synergy_display::<Fiery>));
+      .add_systems(OnEnter(InBattle), spawn_battle_synergies)
+      .add_systems(OnExit(InBattle), despawn_battle_synergies)
+      .add_systems(Update, fade_synergy_highlight);
   }
 }
 
+/// Every synergy of the game, in the order their listeners run
+pub fn register_synergies(registrar: &mut impl RegisterSynergy) {
+  registrar
+    .register::<Fiery>()
+    .register::<Venomous>()
+    .register::<Warrior>()
+    .register::<Holy>()
+    .register::<Guardian>();
+}
+
+#[derive(Component)]
+struct BattleSynergies;
+
+/// Flashes a synergy display in the synergy color when the synergy resolves
+#[derive(Component)]
+pub struct SynergyHighlight {
+  color: Color,
+  timer: Timer,
+}
+
+/// Spawns a display for every registered synergy of the team
 pub fn spawn_synergy_displays(
   commands: &mut RelatedSpawnerCommands<ChildOf>,
+  registry: &SynergyRegistry,
+  team_id: usize,
+) {
+  for spawn_display in &registry.displays {
+    spawn_display(commands, team_id);
+  }
+}
+
+pub(super) fn spawn_synergy_display<S: Synergy>(
+  commands: &mut RelatedSpawnerCommands<ChildOf>,
+  team_id: usize,
 ) {
   commands.spawn((
-    Name::new("Synergy display: Fiery"),
-    SynergyDisplay::<Fiery>::new(0),
+    Name::new(format!("Synergy display: {}", S::name())),
+    SynergyDisplay::<S>::new(team_id),
     related!(Tooltip[(
       Name::new("Synergy Tooltip"),
-      Text::new(Fiery::description()),
+      Text::new(S::description()),
     )]),
   ));
 }
@@ -46,7 +80,7 @@ impl<S: Synergy> SynergyDisplay<S> {
   }
 }
 
-fn update_synergy_display<S: Synergy>(
+pub(super) fn update_synergy_display<S: Synergy>(
   synergies: Res<TeamSynergy<S>>,
   displays: Query<(&mut Text, &mut Node, &SynergyDisplay<S>)>,
 ) {
@@ -56,7 +90,11 @@ fn update_synergy_display<S: Synergy>(
     match synergies.synergies[team_id] {
       Some(synergy) => {
         node.display = Display::Flex;
-        text.0 = format!("{}: {}", S::name(), synergy.intensity());
+        let intensity = synergy.intensity();
+        text.0 = match synergy.break_points().iter().find(|&&break_point| intensity < break_point) {
+          Some(next) => format!("{} {}: {intensity}/{next}", S::name(), synergy.level()),
+          None => format!("{} {}: {intensity} (max)", S::name(), synergy.level()),
+        };
       },
       None => {
         node.display = Display::None;
@@ -65,7 +103,7 @@ fn update_synergy_display<S: Synergy>(
   }
 }
 
-fn update_team_synergy<S: Synergy>(
+pub(super) fn update_team_synergy<S: Synergy>(
   my_team: Single<Entity, With<MyTeam>>,
   enemy_team: Single<Entity, With<EnemyTeam>>,
   children: Query<&Children>,
@@ -84,3 +122,70 @@ fn update_team_synergy<S: Synergy>(
     synergies.synergies[team_id] = S::new(intensity, team_id);
   }
 }
+
+pub(super) fn highlight_synergy<S: Synergy>(
+  In(team_id): In<usize>,
+  mut commands: Commands,
+  displays: Query<(Entity, &SynergyDisplay<S>)>,
+) {
+  for (entity, display) in displays {
+    if display.team_id == team_id {
+      commands.entity(entity).insert(SynergyHighlight {
+        color: S::SYNERGY_COLOR,
+        timer: Timer::from_seconds(SYNERGY_HIGHLIGHT_DURATION, TimerMode::Once),
+      });
+    }
+  }
+}
+
+fn fade_synergy_highlight(
+  mut commands: Commands,
+  time: Res<Time>,
+  displays: Query<(Entity, &mut SynergyHighlight)>,
+) {
+  for (entity, mut highlight) in displays {
+    highlight.timer.tick(time.delta());
+    let alpha = 1.0 - highlight.timer.fraction();
+    commands.entity(entity).insert(BackgroundColor(highlight.color.with_alpha(alpha)));
+    if highlight.timer.finished() {
+      commands.entity(entity).remove::<(SynergyHighlight, BackgroundColor)>();
+    }
+  }
+}
+
+fn spawn_battle_synergies(
+  mut commands: Commands,
+  registry: Res<SynergyRegistry>,
+) {
+  commands.spawn((
+    Name::new("Battle synergies"),
+    Node {
+      position_type: PositionType::Absolute,
+      width: Val::Percent(100.0),
+      justify_content: JustifyContent::SpaceBetween,
+      ..default()
+    },
+    Pickable::IGNORE,
+    BattleSynergies,
+  )).with_children(|commands| {
+    for team_id in 0..2 {
+      commands.spawn((
+        Name::new(format!("Synergies of team {team_id}")),
+        Node {
+          flex_direction: FlexDirection::Column,
+          align_items: if team_id == 0 { AlignItems::Start } else { AlignItems::End },
+          ..default()
+        },
+      )).with_children(|commands| spawn_synergy_displays(commands, &registry, team_id));
+    }
+  });
+}
+
+fn despawn_battle_synergies(
+  mut commands: Commands,
+  battle_synergies: Query<Entity, With<BattleSynergies>>,
+) {
+  for entity in battle_synergies {
+    commands.entity(entity).despawn();
+  }
+}
@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+
+use crate::{battle::{rules::NewStatus, EndRound}, dice::{status::Poison, synergy::{ActionCount, Synergy}, Action, DiceID}};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deref)]
+pub struct Venomous(ActionCount);
+
+impl Synergy for Venomous {
+  type TriggerEvent = EndRound;
+
+  const SYNERGY_COLOR: Color = Color::linear_rgb(0.7, 0.4, 0.9);
+  const ACTION: Action = Action::Poison;
+  const FROM_COUNT: fn(ActionCount) -> Self = Self;
+
+  fn name() -> &'static str {
+    "Venomous"
+  }
+
+  fn description() -> &'static str {
+    "Adds Poison to poisoned opponents at the end of a turn
+    2 -> 1 Poison
+    4 -> 2 Poison
+    6 -> 3 Poison
+    "
+  }
+
+  fn granted_status(&self) -> Option<NewStatus> {
+    Some(NewStatus::new(Poison { stacks: self.level() }))
+  }
+
+  fn grants_to(&self, dice_id: DiceID, entity: &EntityRef) -> bool {
+    dice_id.team_id != self.team_id && entity.contains::<Poison>()
+  }
+
+  fn break_points(&self) -> &[u32] {
+    &[2, 4, 6]
+  }
+}
@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+
+use crate::dice::{events::BeforeResolveDice, synergy::{ActionCount, Synergy}, Action, FacePrototype};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deref)]
+pub struct Warrior(ActionCount);
+
+impl Synergy for Warrior {
+  type TriggerEvent = BeforeResolveDice;
+
+  const SYNERGY_COLOR: Color = Color::linear_rgb(0.9, 0.7, 0.3);
+  const ACTION: Action = Action::Attack;
+  const FROM_COUNT: fn(ActionCount) -> Self = Self;
+
+  fn name() -> &'static str {
+    "Warrior"
+  }
+
+  fn description() -> &'static str {
+    "Attack faces of the team deal more damage
+    3 -> +1 pip
+    6 -> +2 pips
+    9 -> +3 pips
+    "
+  }
+
+  fn trigger_condition(&self, event: Self::TriggerEvent) -> bool {
+    !event.cancelled && event.face != self.boost(event.dice_id.team_id, event.face)
+  }
+
+  fn update_event(&self, mut event: Self::TriggerEvent) -> Self::TriggerEvent {
+    event.face = self.boost(event.dice_id.team_id, event.face);
+    event
+  }
+
+  fn break_points(&self) -> &[u32] {
+    &[3, 6, 9]
+  }
+}
+
+impl Warrior {
+  /// Adds the synergy level to the pips of an attack face of this team
+  pub fn boost(&self, team_id: usize, mut face: FacePrototype) -> FacePrototype {
+    if team_id == self.team_id && face.action == Action::Attack {
+      face.pips = face.pips.map(|pips| pips + self.level());
+    }
+    face
+  }
+}
@@ -1,8 +1,8 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
-use bevy::prelude::*;
+use bevy::{asset::{AssetLoadError, LoadState, RecursiveDependencyLoadState}, prelude::*};
 
-use crate::{constants::{loading_screen::{BAR_SIZE, INNER_BAR_SIZE}, HEIGHT, WIDTH}, dice::Action, states::GameState};
+use crate::{battle::Campaign, constants::{loading_screen::{BAR_SIZE, INNER_BAR_SIZE}, CAMPAIGN_PATH, DICE_CLASSES_FOLDER, HEIGHT, WIDTH}, dice::{Action, StatusRegistry}, manage::Item, states::GameState};
 
 pub struct LoadingScreenPlugin;
 
@@ -47,6 +47,17 @@ impl AssetStore {
     (self.already_loaded as f32) / (self.assets_to_load.len() as f32 + self.already_loaded as f32)
   }
 
+  /// Error of the first asset that failed to load, itself or through one of its dependencies
+  fn failure(&self, server: &AssetServer) -> Option<Arc<AssetLoadError>> {
+    self.assets_to_load.iter().find_map(|handle| match server.get_load_state(handle)? {
+      LoadState::Failed(error) => Some(error),
+      _ => match server.get_recursive_dependency_load_state(handle)? {
+        RecursiveDependencyLoadState::Failed(error) => Some(error),
+        _ => None,
+      },
+    })
+  }
+
   fn is_done(&self) -> bool {
     self.assets_to_load.is_empty()
   }
@@ -55,6 +66,7 @@ impl AssetStore {
 fn populate_asset_store(
   asset_server: Res<AssetServer>,
   mut asset_store: ResMut<AssetStore>,
+  status_registry: Res<StatusRegistry>,
 ) {
   for action in [
     Action::Empty,
@@ -63,11 +75,37 @@ fn populate_asset_store(
     Action::Regenerate,
     Action::Fire,
     Action::Fiery,
+    Action::Block,
+    Action::Poison,
+    Action::Freeze,
+    Action::Cleanse,
   ] {
     let handle = asset_server.load::<Image>(Into::<&'static str>::into(action));
     asset_store.load(action.into(), handle.into());
   }
 
+  for icon in Item::ICONS {
+    let handle = asset_server.load::<Image>(icon);
+    asset_store.load(icon, handle.into());
+  }
+
+  for status in status_registry.known_statuses() {
+    let handle = asset_server.load::<Image>(status.icon);
+    asset_store.load(status.icon, handle.into());
+  }
+
+  {
+    let name = DICE_CLASSES_FOLDER;
+    let handle = asset_server.load_folder(name);
+    asset_store.load(name, handle.into());
+  }
+
+  {
+    let name = CAMPAIGN_PATH;
+    let handle = asset_server.load::<Campaign>(name);
+    asset_store.load(name, handle.into());
+  }
+
   {
     let name = "ui/refresh.png";
     let handle = asset_server.load::<Image>(name);
@@ -86,7 +124,15 @@ fn update_progress(
   asset_server: Res<AssetServer>,
   mut commands: Commands,
   bar_indicator: Single<&mut Transform, With<BarIndicator>>,
+  mut app_exit_events: EventWriter<AppExit>,
 ) {
+  // A failed asset never counts as loaded, the game cannot start without it
+  if let Some(error) = asset_store.failure(&asset_server) {
+    error!("Failed to load the game assets: {error}");
+    app_exit_events.write(AppExit::error());
+    return;
+  }
+
   let progress = asset_store.progress(asset_server.clone());
 
   let mut transform = bar_indicator.into_inner();
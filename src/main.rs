@@ -1,3 +1,5 @@
+use std::{num::NonZeroU32, str::FromStr};
+
 use bevy::prelude::*;
 use bevy::input::common_conditions::input_toggle_active;
 use bevy::window::WindowResolution;
@@ -23,9 +25,20 @@ use crate::menu::plugin::MenuPlugin;
 use crate::manage::plugin::ManagePlugin;
 use crate::loading_screen::LoadingScreenPlugin;
 use crate::utils::tooltip::TooltipPlugin;
-use crate::states::GameState;
+use crate::states::{GameState, InBattle};
+use crate::battle::print_balance_report;
+use crate::utils::rng::{FixedSeed, GameRng};
+
+const DEFAULT_BATTLES: NonZeroU32 = NonZeroU32::new(1000).unwrap();
 
 fn main() {
+  let seed = flag_value::<u64>("--seed").flatten();
+  // Zero battles does not parse, there would be no report to print
+  if let Some(battles) = flag_value::<NonZeroU32>("--simulate") {
+    print_balance_report(battles.unwrap_or(DEFAULT_BATTLES), seed.unwrap_or_default());
+    return;
+  }
+
   let mut app = App::new();
   app
     .add_plugins(AsyncPlugin::default_settings())
@@ -41,10 +54,16 @@ fn main() {
       })
       .set(ImagePlugin::default_nearest())
     )
+    .insert_resource(seed.map(GameRng::new).unwrap_or_default())
     .init_state::<GameState>()
+    .add_computed_state::<InBattle>()
     .add_plugins((MenuPlugin, ManagePlugin, BattlePlugin, CameraPlugin, DicePlugin, LoadingScreenPlugin, TooltipPlugin))
     .add_plugins(EguiPlugin { enable_multipass_for_primary_context: true });
   
+  if seed.is_some() {
+    app.insert_resource(FixedSeed);
+  }
+
   if cfg!(debug_assertions) {
     app
       .add_plugins(
@@ -53,3 +72,21 @@ fn main() {
   }
   app.run();
 }
+
+// `--simulate <battles>` runs the headless battle simulator instead of opening a window
+// `--seed <seed>` makes every run (or simulation) reproducible
+// A value that is given but does not parse exits, rather than silently falling back to a random run
+fn flag_value<T: FromStr>(flag: &str) -> Option<Option<T>> {
+  let args: Vec<String> = std::env::args().collect();
+  let position = args.iter().position(|arg| arg == flag)?;
+  let Some(value) = args.get(position + 1).filter(|value| !value.starts_with("--")) else {
+    return Some(None);
+  };
+  match value.parse() {
+    Ok(value) => Some(Some(value)),
+    Err(_) => {
+      eprintln!("Invalid value for {flag}: {value}");
+      std::process::exit(1);
+    },
+  }
+}
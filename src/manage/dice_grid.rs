@@ -8,7 +8,7 @@ impl Plugin for DiceGridPlugin {
   fn build(&self, app: &mut App) {
     app
       .add_systems(Update, (
-        register_changes::<Tile>, update_grids::<Tile>,
+        register_changes::<Tile>, register_layout_changes::<Tile>, update_grids::<Tile>,
         register_changes::<DiceTemplate>, update_grids::<DiceTemplate>,
         register_changes::<Dice>, update_grids::<Dice>,
       ));
@@ -75,6 +75,18 @@ fn register_changes<Faces: Gridable>(
   }
 }
 
+/// Lays the grid out again when the collection itself changes, like a rotated tile
+fn register_layout_changes<Faces: Gridable>(
+  collection: Query<&DiceGrid, Changed<Faces>>,
+  mut commands: Commands,
+) {
+  for DiceGrid { grid } in collection {
+    commands
+      .entity(*grid)
+      .insert(UpdateGrid);
+  }
+}
+
 fn update_grids<Faces: Gridable>(
   mut commands: Commands,
   grids: Query<(Entity, &DiceGridOf), With<UpdateGrid>>,
@@ -0,0 +1,177 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  battle::Campaign,
+  constants::{ui::COINS_NUMBER_SIZE, CONSOLATION_INCOME, INTEREST_STEP, MAX_INTEREST},
+  manage::plugin::{Coins, ShopRound},
+  states::GameState,
+};
+
+pub struct EconomyPlugin;
+
+impl Plugin for EconomyPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<Streak>()
+      .init_resource::<Losses>()
+      .init_resource::<Income>()
+      .add_systems(OnEnter(GameState::Manage), spawn_income_breakdown)
+      .add_systems(OnExit(GameState::Manage), despawn_income_breakdown)
+      .add_systems(Update, dismiss_income_breakdown.run_if(in_state(GameState::Manage)));
+  }
+}
+
+/// Consecutive battles won or lost, both paying a bonus once long enough
+#[derive(Resource, Default, Clone, Copy, Serialize, Deserialize)]
+pub enum Streak {
+  #[default]
+  None,
+  Wins(u32),
+  Losses(u32),
+}
+
+impl Streak {
+  fn record(&mut self, won: bool) {
+    *self = match (*self, won) {
+      (Self::Wins(wins), true) => Self::Wins(wins + 1),
+      (_, true) => Self::Wins(1),
+      (Self::Losses(losses), false) => Self::Losses(losses + 1),
+      (_, false) => Self::Losses(1),
+    };
+  }
+
+  fn bonus(&self) -> u32 {
+    let length = match *self {
+      Self::None => 0,
+      Self::Wins(length) | Self::Losses(length) => length,
+    };
+    match length {
+      0..=1 => 0,
+      2..=3 => 1,
+      4 => 2,
+      _ => 3,
+    }
+  }
+}
+
+/// Battles lost during the run, which ends once they reach the lives of the `Campaign`
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct Losses(u32);
+
+impl Losses {
+  pub fn new(losses: u32) -> Self {
+    Self(losses)
+  }
+}
+
+/// Coins earned by the last battle, line by line, until shown on the manage screen
+#[derive(Resource, Default)]
+pub struct Income(Vec<(&'static str, u32)>);
+
+/// One coin for every `INTEREST_STEP` coins saved, up to `MAX_INTEREST`
+fn interest(coins: u32) -> u32 {
+  (coins / INTEREST_STEP).min(MAX_INTEREST)
+}
+
+/// Pays the coins earned by the battle that just ended, must run before `ShopRound` moves on
+pub fn collect_income(
+  In(won): In<bool>,
+  mut coins: ResMut<Coins>,
+  mut streak: ResMut<Streak>,
+  mut income: ResMut<Income>,
+  shop_round: Res<ShopRound>,
+  campaign: Res<Campaign>,
+) {
+  streak.record(won);
+
+  let mut lines = Vec::new();
+  if won {
+    lines.push(("Victory", campaign.round(**shop_round).reward));
+  } else {
+    lines.push(("Consolation", CONSOLATION_INCOME));
+  }
+  lines.push(("Interest", interest(**coins)));
+  let streak_name = if won { "Win streak" } else { "Loss streak" };
+  lines.push((streak_name, streak.bonus()));
+  lines.retain(|&(_, amount)| amount > 0);
+
+  **coins += lines.iter().map(|&(_, amount)| amount).sum::<u32>();
+  income.0 = lines;
+}
+
+#[derive(Component)]
+struct IncomeBreakdown;
+
+/// Lists the income of the last battle, clicking it closes it
+fn spawn_income_breakdown(
+  mut commands: Commands,
+  mut income: ResMut<Income>,
+  losses: Res<Losses>,
+  campaign: Res<Campaign>,
+) {
+  let lines = std::mem::take(&mut income.0);
+  if lines.is_empty() { return; }
+
+  let total: u32 = lines.iter().map(|&(_, amount)| amount).sum();
+  let mut text: String = lines
+    .iter()
+    .map(|(name, amount)| format!("{name}: +{amount}\n"))
+    .collect();
+  text += &format!("Total: +{total}");
+  if **losses > 0 {
+    text += &format!("\nLives left: {}", campaign.lives.saturating_sub(**losses));
+  }
+
+  commands.spawn((
+    Name::new("Income breakdown"),
+    Node {
+      position_type: PositionType::Absolute,
+      width: Val::Percent(100.0),
+      height: Val::Percent(100.0),
+      justify_content: JustifyContent::Center,
+      align_items: AlignItems::Center,
+      ..default()
+    },
+    Button,
+    GlobalZIndex(2),
+    IncomeBreakdown,
+  )).with_children(|commands| {
+    commands.spawn((
+      Node {
+        padding: UiRect::all(Val::Px(COINS_NUMBER_SIZE)),
+        ..default()
+      },
+      BackgroundColor(Color::srgb(0.8, 0.8, 0.8)),
+      Text::new(text),
+      TextFont {
+        font_size: COINS_NUMBER_SIZE,
+        ..default()
+      },
+      TextColor(Color::BLACK),
+      TextLayout::new_with_justify(JustifyText::Center),
+      Pickable::IGNORE,
+    ));
+  });
+}
+
+#[allow(clippy::type_complexity)]
+fn dismiss_income_breakdown(
+  mut commands: Commands,
+  breakdowns: Query<(Entity, &Interaction), (With<IncomeBreakdown>, Changed<Interaction>)>,
+) {
+  for (breakdown, interaction) in breakdowns {
+    if *interaction == Interaction::Pressed {
+      commands.entity(breakdown).despawn();
+    }
+  }
+}
+
+fn despawn_income_breakdown(
+  mut commands: Commands,
+  breakdowns: Query<Entity, With<IncomeBreakdown>>,
+) {
+  for breakdown in breakdowns {
+    commands.entity(breakdown).despawn();
+  }
+}
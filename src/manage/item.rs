@@ -0,0 +1,183 @@
+use bevy::{ecs::relationship::RelatedSpawnerCommands, prelude::*};
+use rand::{rngs::StdRng, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  constants::GRID_FACE_SIZE,
+  dice::{DiceClasses, DiceTemplate, Face, Health},
+  loading_screen::AssetStore,
+  manage::{plugin::Coins, team::TeamSpot, tile::Buyable},
+  states::InBattle,
+};
+
+pub struct ItemPlugin;
+
+impl Plugin for ItemPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_systems(OnExit(InBattle), expire_elixirs);
+  }
+}
+
+/// Shop items used by dropping them on a dice of the team
+#[derive(Component, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Item {
+  /// Raises the dice by one level of its class
+  Upgrade,
+  /// Raises the max health of the dice for the rest of the run
+  Potion { hp: u32 },
+  /// Raises the health of the dice for the next battle only
+  Elixir { hp: u32 },
+}
+
+impl Buyable for Item {
+  fn price(&self) -> u32 {
+    match self {
+      Self::Upgrade => 3,
+      Self::Potion { .. } => 3,
+      Self::Elixir { .. } => 1,
+    }
+  }
+}
+
+/// Health given by elixirs, taken back once the battle is over
+#[derive(Component)]
+pub struct ElixirHealth(u32);
+
+#[derive(Component)]
+#[relationship(relationship_target = ItemNode)]
+pub struct ItemNodeOf(Entity);
+
+#[derive(Component)]
+#[relationship_target(relationship = ItemNodeOf, linked_spawn)]
+pub struct ItemNode(Entity);
+
+impl Item {
+  pub const ICONS: [&'static str; 3] = ["actions/potion_yellow.png", "actions/potion_red.png", "actions/potion_green.png"];
+
+  pub fn random(rng: &mut StdRng) -> Self {
+    *[
+      Self::Upgrade,
+      Self::Potion { hp: 2 },
+      Self::Elixir { hp: 4 },
+    ].choose(rng).unwrap()
+  }
+
+  pub fn icon(&self) -> &'static str {
+    match self {
+      Self::Upgrade => Self::ICONS[0],
+      Self::Potion { .. } => Self::ICONS[1],
+      Self::Elixir { .. } => Self::ICONS[2],
+    }
+  }
+
+  pub fn description(&self) -> String {
+    match self {
+      Self::Upgrade => "Level up".into(),
+      Self::Potion { hp } => format!("+{hp} HP"),
+      Self::Elixir { hp } => format!("+{hp} HP\nnext battle"),
+    }
+  }
+
+  pub fn spawn(self, commands: &mut RelatedSpawnerCommands<ChildOf>) {
+    commands.spawn((
+      Name::new("Item"),
+      self,
+    ));
+  }
+
+  pub fn spawn_node<'a>(self, commands: &'a mut RelatedSpawnerCommands<ChildOf>, item: Entity, asset_store: &AssetStore) -> EntityCommands<'a> {
+    let mut node = commands.spawn((
+      Name::new("Item node"),
+      Node {
+        width: Val::Px(GRID_FACE_SIZE * 2.0),
+        height: Val::Px(GRID_FACE_SIZE * 2.0),
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+      },
+      ImageNode::new(asset_store.get(self.icon())),
+      ItemNodeOf(item),
+    ));
+    node.with_child((
+      Text::new(self.description()),
+      TextColor(Color::BLACK),
+      TextLayout::new_with_justify(JustifyText::Center),
+      Pickable::IGNORE,
+    ));
+    node
+  }
+}
+
+/// Uses the dropped item on the dice of the team under it, or puts the item back in its spot
+#[allow(clippy::too_many_arguments)]
+pub fn drop_item(
+  In((node, item)): In<(Entity, Entity)>,
+  mut commands: Commands,
+  mut nodes: Query<&mut Node>,
+  layout: Query<(&GlobalTransform, &ComputedNode)>,
+  spots: Query<(&TeamSpot, &GlobalTransform, &ComputedNode)>,
+  items: Query<&Item>,
+  mut templates: Query<(&mut DiceTemplate, &mut Health, &Children, Option<&mut ElixirHealth>)>,
+  mut faces: Query<&mut Face>,
+  classes: Res<DiceClasses>,
+  mut coins: ResMut<Coins>,
+) {
+  let item_kind = *items.get(item).unwrap();
+  let position = layout.get(node).unwrap().0.translation().truncate();
+  let target = spots
+    .iter()
+    .find(|(_, transform, spot)| transform.translation().truncate().distance(position) < spot.size.x / 2.0)
+    .map(|(&TeamSpot(template), ..)| template);
+
+  let used = **coins >= item_kind.price() && target.is_some_and(|template| {
+    let Ok((mut dice_template, mut health, template_faces, elixir)) = templates.get_mut(template) else { return false };
+    match item_kind {
+      Item::Upgrade => {
+        let Some(class) = dice_template.class.as_deref().and_then(|class| classes.get(class)) else { return false };
+        if dice_template.level >= class.max_level() {
+          return false;
+        }
+        let old_hp = class.hp(dice_template.level);
+        dice_template.level += 1;
+        for &(face_id, prototype) in class.upgrade(dice_template.level) {
+          faces.get_mut(template_faces[face_id as usize]).unwrap().prototype = prototype;
+        }
+        // Only the health given by the class changes, potions and elixirs keep adding to it
+        *health = Health::new(health.max + class.hp(dice_template.level) - old_hp);
+      },
+      Item::Potion { hp } => {
+        *health = Health::new(health.max + hp);
+      },
+      Item::Elixir { hp } => {
+        *health = Health::new(health.max + hp);
+        match elixir {
+          Some(mut elixir) => elixir.0 += hp,
+          None => { commands.entity(template).insert(ElixirHealth(hp)); },
+        }
+      },
+    }
+    true
+  });
+
+  if used {
+    **coins -= item_kind.price();
+    commands.entity(item).despawn();
+  } else {
+    let mut node = nodes.get_mut(node).unwrap();
+    node.position_type = PositionType::Relative;
+    node.left = Val::Auto;
+    node.top = Val::Auto;
+  }
+}
+
+fn expire_elixirs(
+  mut commands: Commands,
+  templates: Query<(Entity, &mut Health, &ElixirHealth)>,
+) {
+  for (template, mut health, &ElixirHealth(hp)) in templates {
+    *health = Health::new(health.max.saturating_sub(hp));
+    commands.entity(template).remove::<ElixirHealth>();
+  }
+}
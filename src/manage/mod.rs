@@ -1,5 +1,10 @@
 pub mod plugin;
 mod dice_grid;
+mod team;
 mod tile;
+mod item;
+pub mod economy;
+pub mod save;
 
 pub use dice_grid::DiceGrid;
+pub use item::Item;
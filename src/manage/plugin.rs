@@ -1,5 +1,6 @@
-use bevy::prelude::*;
-use crate::{battle::{clean_up_game, Challenge}, constants::{dice_texture::TARGET_SIZE, ui::{BUTTON_SIZE, COINS_NUMBER_SIZE, REFRESH_BUTTON_SIZE, ROUND_NUMBER_SIZE}, DICE_SIZE, REFRESH_PRICE, SHOP_ITEMS_COUNT}, dice::{spawn_synergy_displays, DiceTemplate, Face, FaceSource, HealthBar}, loading_screen::AssetStore, manage::{dice_grid::{DiceGrid, DiceGridOf, DiceGridPlugin}, tile::{Buyable, Tile}}, states::GameState};
+use bevy::{ecs::relationship::RelatedSpawnerCommands, prelude::*};
+use rand::Rng;
+use crate::{battle::{clean_up_game, Campaign}, constants::{dice_texture::TARGET_SIZE, ui::{BUTTON_SIZE, COINS_NUMBER_SIZE, REFRESH_BUTTON_SIZE, ROUND_NUMBER_SIZE, TILE_FRAME_WIDTH}, DICE_OFFER_CHANCE, REFRESH_PRICE, SHOP_ITEMS_COUNT, ITEM_OFFER_CHANCE, STICKER_OFFER_CHANCE}, dice::{spawn_synergy_displays, SynergyRegistry, DiceClasses, DiceTemplate, DiceTemplateBuilder, Face, FaceSource}, loading_screen::AssetStore, manage::{economy::{EconomyPlugin, Losses}, dice_grid::{DiceGrid, DiceGridOf, DiceGridPlugin}, save::{save_run, SavedShopItem, SavedTile}, team::{buy_dice, spawn_template_spot, SellArea, TeamDisplay, TeamPlugin}, tile::{spawn_price_tag, Buyable, Tile}, item::{drop_item, Item, ItemPlugin}}, states::GameState, utils::rng::GameRng};
 
 pub struct ManagePlugin;
 
@@ -7,12 +8,13 @@ impl Plugin for ManagePlugin {
   fn build(&self, app: &mut App) {
     app
       .init_resource::<Coins>()
-      .add_plugins(DiceGridPlugin)
+      .add_plugins((DiceGridPlugin, TeamPlugin, ItemPlugin, EconomyPlugin))
       .init_resource::<ShopRound>()
-      .add_systems(OnEnter(GameState::Manage), (spawn_enemy, spawn_shop, spawn_manage).chain())
-      .add_systems(OnExit(GameState::Manage), (despawn_shop, despawn_manage).chain())
+      .add_systems(OnEnter(GameState::Manage), (start_shop_round, spawn_enemy, spawn_shop, spawn_manage, save_run).chain())
+      .add_systems(OnExit(GameState::Manage), (clear_shop, despawn_manage).chain())
       .add_systems(Update, button_actions.run_if(in_state(GameState::Manage)))
-      .add_systems(Update, (update_coins, refresh_shop, update_shop_spots).run_if(in_state(GameState::Manage)));
+      .add_systems(Update, (update_coins, refresh_shop, update_shop_spots, toggle_shop_locks, unlock_empty_spots, toggle_enemy_preview, turn_dragged_tile).run_if(in_state(GameState::Manage)))
+      .add_systems(PostUpdate, refresh_turned_tile_overlap.after(TransformSystem::TransformPropagate).run_if(in_state(GameState::Manage)));
   }
 }
 
@@ -34,6 +36,13 @@ enum ButtonAction {
 #[derive(Component)]
 struct CoinsDisplay;
 
+/// Clicking it shows or hides the `EnemyPreview`, which stays open while shopping
+#[derive(Component)]
+struct EnemyPreviewToggle;
+
+#[derive(Component)]
+struct EnemyPreview;
+
 fn update_coins(
   display: Single<&mut Text, With<CoinsDisplay>>,
   coins: Res<Coins>,
@@ -46,6 +55,14 @@ fn update_coins(
 #[derive(Component)]
 pub struct MyTeam;
 
+fn start_shop_round(
+  mut rng: ResMut<GameRng>,
+  shop_round: Res<ShopRound>,
+  losses: Res<Losses>,
+) {
+  rng.start_round(**shop_round, **losses);
+}
+
 #[derive(Component)]
 pub struct EnemyTeam;
 
@@ -54,23 +71,35 @@ pub fn spawn_enemy(
   mut images: ResMut<Assets<Image>>,
   enemy_team: Option<Single<Entity, With<EnemyTeam>>>,
   shop_round: Res<ShopRound>,
-) {
+  classes: Res<DiceClasses>,
+  campaign: Res<Campaign>,
+) -> Result {
   if let Some(entity) = enemy_team {
     commands.entity(*entity).despawn();
   }
+  let team = campaign.round(shop_round.0).team(&classes)?;
   commands.spawn((
     Name::new("Enemy team"),
     EnemyTeam,
   )).with_children(|commands| {
-    for builder in Challenge::new(shop_round.0).0 {
+    for builder in team {
       builder.spawn(commands, &mut images);
     }
   });
+  Ok(())
 }
 
 #[derive(Component)]
 pub struct Shop;
 
+/// Marks a `Shop` loaded from a save, already stocked for the current round
+#[derive(Component)]
+pub struct RestoredShop;
+
+/// Marks a spot of the `Shop` whose item is kept through refreshes and battles
+#[derive(Component)]
+pub struct Locked;
+
 #[derive(Component)]
 #[relationship(relationship_target = ShopSpotOf)]
 pub struct ShopSpot(Entity);
@@ -79,10 +108,42 @@ pub struct ShopSpot(Entity);
 #[relationship_target(relationship = ShopSpot)]
 pub struct ShopSpotOf(Entity);
 
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn spawn_shop(
   mut commands: Commands,
   mut images: ResMut<Assets<Image>>,
+  mut rng: ResMut<GameRng>,
+  classes: Res<DiceClasses>,
+  shop: Option<Single<(Entity, &Children, Has<RestoredShop>), With<Shop>>>,
+  stocked_spots: Query<(), With<Children>>,
+  locked: Query<(), With<Locked>>,
+  shop_round: Res<ShopRound>,
 ) {
+  // The shop lives through the whole run, only its empty spots are restocked
+  if let Some(shop) = shop {
+    let (shop, spots, restored) = *shop;
+    if restored {
+      // The saved shop was already restocked, the same draws are made again so the stream
+      // continues like it did in the original run
+      for &spot in spots {
+        if !locked.contains(spot) {
+          roll_shop_item(&mut rng, &classes, **shop_round);
+        }
+      }
+      commands.entity(shop).remove::<RestoredShop>();
+      return;
+    }
+    for &spot in spots {
+      if stocked_spots.contains(spot) { continue; }
+      commands
+        .entity(spot)
+        .with_children(|commands| {
+          spawn_shop_item(&mut images, &mut rng, &classes, **shop_round, commands);
+        });
+    }
+    return;
+  }
+
   commands.spawn((
       Name::new("Shop"),
       Shop,
@@ -94,39 +155,114 @@ fn spawn_shop(
             Name::new("Shop spot"),
           ))
           .with_children(|commands| {
-            Tile::spawn(&mut images, commands);
+            spawn_shop_item(&mut images, &mut rng, &classes, **shop_round, commands);
           });
       }
     });
 }
 
+/// Fills a shop spot with a tile, a sticker, an item or a level 1 dice of a random class
+fn spawn_shop_item(images: &mut Assets<Image>, rng: &mut GameRng, classes: &DiceClasses, shop_round: u32, commands: &mut RelatedSpawnerCommands<ChildOf>) {
+  roll_shop_item(rng, classes, shop_round).spawn(images, commands);
+}
+
+fn roll_shop_item(rng: &mut GameRng, classes: &DiceClasses, shop_round: u32) -> SavedShopItem {
+  let rng = rng.shop();
+  let roll = rng.gen::<f64>();
+  if roll < DICE_OFFER_CHANCE {
+    let classes = classes.sorted();
+    let class = classes[rng.gen_range(0..classes.len())];
+    SavedShopItem::Dice(DiceTemplateBuilder::from_class(class, 1))
+  } else if roll < DICE_OFFER_CHANCE + ITEM_OFFER_CHANCE {
+    SavedShopItem::Item(Item::random(rng))
+  } else if roll < DICE_OFFER_CHANCE + ITEM_OFFER_CHANCE + STICKER_OFFER_CHANCE {
+    let (tile, faces) = Tile::random_sticker(rng);
+    SavedShopItem::Tile(SavedTile { tile, faces })
+  } else {
+    let (tile, faces) = Tile::random(rng, shop_round);
+    SavedShopItem::Tile(SavedTile { tile, faces })
+  }
+}
+
+#[allow(clippy::type_complexity)]
 fn update_shop_spots(
   mut commands: Commands,
-  shop_spots: Query<(&Children, &ShopSpotOf), Changed<Children>>,
+  // Locked spots keep their children when the manage screen is spawned again
+  shop_spots: Query<(&Children, &ShopSpotOf), Or<(Changed<Children>, Added<ShopSpotOf>)>>,
+  templates: Query<&DiceTemplate>,
+  tiles: Query<&Tile>,
+  items: Query<&Item>,
+  asset_store: Res<AssetStore>,
 ) {
   for (children, ShopSpotOf(spot)) in shop_spots {
     if children.is_empty() { continue };
     assert!(children.len() == 1);
     let child = children[0];
 
+    if let Ok(template) = templates.get(child) {
+      commands
+        .entity(*spot)
+        .with_children(|commands| {
+          spawn_price_tag(commands, child, template.price());
+          DiceGrid::spawn(commands, child)
+            .observe(move |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+              commands.run_system_cached_with(buy_dice, child);
+            });
+        });
+      continue;
+    }
+
+    if let Ok(&item) = items.get(child) {
+      commands
+        .entity(*spot)
+        .with_children(|commands| {
+          spawn_price_tag(commands, child, item.price());
+          let node = item.spawn_node(commands, child, &asset_store).id();
+          commands.commands()
+            .entity(node)
+            .observe(drag_tile(node))
+            .observe(move |release: Trigger<Pointer<Released>>, mut commands: Commands| {
+              if release.button == PointerButton::Primary {
+                commands.run_system_cached_with(drop_item, (node, child));
+              }
+            });
+        });
+      continue;
+    }
+
     commands
       .entity(*spot)
       .with_children(|commands| {
-        let grid_tile = DiceGrid::spawn(commands, child).id();
+        let tile = tiles.get(child).unwrap();
+        spawn_price_tag(commands, child, tile.price());
+        let grid_tile = DiceGrid::spawn(commands, child)
+          .insert(Outline::new(Val::Px(TILE_FRAME_WIDTH), Val::ZERO, tile.rarity().color()))
+          .id();
 
         commands.commands()
           .entity(grid_tile)
           .observe(drag_tile(grid_tile))
-          .observe(
-            on_drag(grid_tile)
-              .pipe(overlap_tile_template)
-              .pipe(mark_faces)
-          )
-          .observe(
-            on_release(grid_tile)
-              .pipe(overlap_tile_template)
-              .pipe(apply_tile)
-          );
+          .observe(move |drag: Trigger<Pointer<DragStart>>, mut commands: Commands| {
+            if drag.button == PointerButton::Primary {
+              commands.entity(grid_tile).insert(DraggedTile);
+            }
+          })
+          .observe(move |drag: Trigger<Pointer<Drag>>, mut commands: Commands| {
+            if drag.button == PointerButton::Primary {
+              commands.run_system_cached_with(overlap_tile_template.pipe(mark_faces), grid_tile);
+            }
+          })
+          .observe(move |pressed: Trigger<Pointer<Pressed>>, mut commands: Commands, dragged: Query<(), With<DraggedTile>>| {
+            if pressed.button == PointerButton::Secondary && dragged.contains(grid_tile) {
+              commands.run_system_cached_with(turn_tile, (grid_tile, TileTurn::Rotate));
+            }
+          })
+          .observe(move |release: Trigger<Pointer<Released>>, mut commands: Commands| {
+            if release.button == PointerButton::Primary {
+              commands.entity(grid_tile).remove::<DraggedTile>();
+              commands.run_system_cached_with(overlap_tile_template.pipe(apply_tile), grid_tile);
+            }
+          });
       });
   }
 }
@@ -134,13 +270,16 @@ fn update_shop_spots(
 #[derive(Component)]
 struct RefreshButton;
 
-#[allow(clippy::type_complexity)]
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 fn refresh_shop(
   mut commands: Commands,
-  shop_spots: Query<Entity, With<ShopSpotOf>>,
+  shop_spots: Query<Entity, (With<ShopSpotOf>, Without<Locked>)>,
   mut images: ResMut<Assets<Image>>,
   button: Option<Single<&Interaction, (With<RefreshButton>, Changed<Interaction>)>>,
   mut coins: ResMut<Coins>,
+  mut rng: ResMut<GameRng>,
+  classes: Res<DiceClasses>,
+  shop_round: Res<ShopRound>,
 ) {
   if button.is_none() || **button.unwrap() != Interaction::Pressed || **coins < REFRESH_PRICE {
     return;
@@ -151,26 +290,77 @@ fn refresh_shop(
       .entity(spot)
       .despawn_related::<Children>()
       .with_children(|commands| {
-        Tile::spawn(&mut images, commands);
+        spawn_shop_item(&mut images, &mut rng, &classes, **shop_round, commands);
       });
   }
 }
 
-fn despawn_shop(
+/// Empties the unlocked spots, the shop is restocked when the manage screen is entered again
+fn clear_shop(
+  mut commands: Commands,
+  shop: Option<Single<&Children, With<Shop>>>,
+  locked: Query<(), With<Locked>>,
+) {
+  // Going back to the menu already despawned the shop
+  let Some(shop) = shop else { return };
+  for &spot in *shop {
+    if !locked.contains(spot) {
+      commands.entity(spot).despawn_related::<Children>();
+    }
+  }
+}
+
+/// Toggles the `Locked` marker of the shop spot
+#[derive(Component)]
+struct LockButton(Entity);
+
+fn lock_text(locked: bool) -> &'static str {
+  if locked { "Locked" } else { "Lock" }
+}
+
+#[allow(clippy::type_complexity)]
+fn toggle_shop_locks(
+  mut commands: Commands,
+  buttons: Query<(&Interaction, &LockButton, &mut Text), Changed<Interaction>>,
+  locked: Query<(), With<Locked>>,
+) {
+  for (interaction, &LockButton(spot), mut text) in buttons {
+    if *interaction != Interaction::Pressed { continue; }
+    if locked.contains(spot) {
+      commands.entity(spot).remove::<Locked>();
+    } else {
+      commands.entity(spot).insert(Locked);
+    }
+    text.0 = lock_text(!locked.contains(spot)).into();
+  }
+}
+
+/// A locked spot whose item was bought has nothing left to keep, so refreshes restock it again
+#[allow(clippy::type_complexity)]
+fn unlock_empty_spots(
   mut commands: Commands,
-  shop: Single<Entity, With<Shop>>,
+  spots: Query<Entity, (With<Locked>, With<ShopSpotOf>, Without<Children>)>,
+  mut buttons: Query<(&LockButton, &mut Text)>,
 ) {
-  commands
-    .entity(*shop)
-    .despawn();
+  for spot in spots {
+    commands.entity(spot).remove::<Locked>();
+    for (_, mut text) in buttons.iter_mut().filter(|(&LockButton(button_spot), _)| button_spot == spot) {
+      text.0 = lock_text(false).into();
+    }
+  }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_manage(
   mut commands: Commands,
-  my_team: Single<&Children, With<MyTeam>>,
+  enemy_team: Single<&Children, With<EnemyTeam>>,
   shop: Single<&Children, With<Shop>>,
+  locked: Query<(), With<Locked>>,
   shop_round: Res<ShopRound>,
+  losses: Res<Losses>,
+  campaign: Res<Campaign>,
   asset_store: Res<AssetStore>,
+  synergies: Res<SynergyRegistry>,
 ) {
   commands.spawn((
     Name::new("Manage"),
@@ -205,7 +395,7 @@ fn spawn_manage(
           },
           ..default()
         },
-        Text::new(format!("Round {}/4", shop_round.0)),
+        Text::new(format!("Round {}/{}  Lives {}", shop_round.0, campaign.round_count(), campaign.lives.saturating_sub(**losses))),
         TextFont {
           font_size: ROUND_NUMBER_SIZE,
           ..default()
@@ -244,7 +434,7 @@ fn spawn_manage(
           ..default()
         },
         ZIndex(1),
-      )).with_children(spawn_synergy_displays);
+      )).with_children(|commands| spawn_synergy_displays(commands, &synergies, 0));
 
       commands.spawn((
         Name::new("Dice display"),
@@ -259,25 +449,31 @@ fn spawn_manage(
           ..default()
         },
         BackgroundColor(Color::srgb(0.6, 0.4, 0.2)),
+        TeamDisplay,
+      ));
+
+      commands.spawn((
+        Name::new("Enemy preview"),
+        Node {
+          display: Display::None,
+          position_type: PositionType::Absolute,
+          width: Val::Percent(70.0),
+          height: Val::Percent(100.0),
+          justify_content: JustifyContent::Center,
+          flex_wrap: FlexWrap::Wrap,
+          align_content: AlignContent::SpaceAround,
+          align_items: AlignItems::Center,
+          row_gap: Val::Px(-TARGET_SIZE),
+          column_gap: Val::Px(TARGET_SIZE / 8.0),
+          ..default()
+        },
+        BackgroundColor(Color::srgb(0.4, 0.2, 0.2)),
+        ZIndex(2),
+        Pickable::IGNORE,
+        EnemyPreview,
       )).with_children(|commands| {
-        for &template in *my_team {
-          commands.spawn((
-            Name::new("Dice template spot"),
-            Node::default(),
-          )).with_children(|commands| {
-            DiceGrid::spawn(commands, template);
-            commands.spawn((
-              Name::new("Health bar container"),
-              Node {
-                position_type: PositionType::Absolute,
-                bottom: Val::Px(-0.2 * DICE_SIZE),
-                margin: UiRect { left: Val::Auto, right: Val::Auto, ..default() },
-                ..default()
-              },
-            )).with_children(|commands| {
-              HealthBar::spawn(commands, template);
-            });
-          });
+        for &template in *enemy_team {
+          spawn_template_spot(commands, template);
         }
       });
       commands.spawn((
@@ -288,6 +484,7 @@ fn spawn_manage(
           ..default()
         },
         BackgroundColor(Color::srgb(0.8, 0.6, 0.4)),
+        SellArea,
       )).with_children(|commands| {
         commands.spawn((
           Name::new("Refresh button"),
@@ -318,6 +515,19 @@ fn spawn_manage(
               },
               ShopSpot(shop_spot),
               Pickable::IGNORE,
+            ))
+            .with_child((
+              Name::new("Lock button"),
+              Button,
+              LockButton(shop_spot),
+              Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(0.0),
+                top: Val::Px(0.0),
+                ..default()
+              },
+              Text::new(lock_text(locked.contains(shop_spot))),
+              TextColor(Color::BLACK),
             ));
         }
       });
@@ -366,6 +576,33 @@ fn spawn_manage(
         });
       });
 
+      commands.spawn((
+        Name::new("Upcoming challenge"),
+        Button,
+        EnemyPreviewToggle,
+        Node {
+          height: Val::Percent(100.0),
+          flex_direction: FlexDirection::Column,
+          justify_content: JustifyContent::Center,
+          align_items: AlignItems::Center,
+          ..default()
+        },
+      )).with_children(|commands| {
+        commands.spawn((
+          Text::new(format!("Next enemy (reward: {})", campaign.round(shop_round.0).reward)),
+          TextFont { font_size: 30.0, ..default() },
+          TextColor(Color::WHITE),
+        ));
+        commands.spawn((
+          Name::new("Enemy synergies"),
+          Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            ..default()
+          },
+        )).with_children(|commands| spawn_synergy_displays(commands, &synergies, 1));
+      });
+
       commands.spawn((
         Name::new("Right side"),
         Node {
@@ -401,6 +638,19 @@ fn spawn_manage(
   });
 }
 
+#[allow(clippy::type_complexity)]
+fn toggle_enemy_preview(
+  toggle: Option<Single<&Interaction, (With<EnemyPreviewToggle>, Changed<Interaction>)>>,
+  mut preview: Single<&mut Node, With<EnemyPreview>>,
+) {
+  let Some(interaction) = toggle else { return };
+  if **interaction != Interaction::Pressed { return; }
+  preview.display = match preview.display {
+    Display::None => Display::Flex,
+    _ => Display::None,
+  };
+}
+
 fn drag_tile(tile: Entity) -> impl IntoSystem<Trigger<'static, Pointer<Drag>>, (), ()> { 
   let closure = move |
     drag: Trigger<Pointer<Drag>>,
@@ -410,6 +660,8 @@ fn drag_tile(tile: Entity) -> impl IntoSystem<Trigger<'static, Pointer<Drag>>, (
     fn size(node: &ComputedNode) -> Vec2 {
       node.size * node.inverse_scale_factor
     }
+    // Only the primary button drags, the secondary one turns the tile meanwhile
+    if drag.button != PointerButton::Primary { return; }
     let delta = drag.delta;
 
     let (mut node, &ChildOf(parent)) = tiles.get_mut(tile).unwrap();
@@ -432,17 +684,55 @@ fn drag_tile(tile: Entity) -> impl IntoSystem<Trigger<'static, Pointer<Drag>>, (
   IntoSystem::into_system(closure)
 }
 
-fn on_drag(tile: Entity) -> impl Fn(Trigger<Pointer<Drag>>) -> Entity {move |
-  _trigger: Trigger<Pointer<Drag>>,
-| {
-  tile
-}}
+/// Marks the grid of the tile being dragged, which the player can rotate and flip
+#[derive(Component)]
+struct DraggedTile;
+
+#[derive(Clone, Copy)]
+enum TileTurn {
+  Rotate,
+  Flip,
+}
+
+fn turn_tile(
+  In((grid, turn)): In<(Entity, TileTurn)>,
+  grids: Query<&DiceGridOf>,
+  mut tiles: Query<&mut Tile>,
+) {
+  let Ok(mut tile) = tiles.get_mut(grids.get(grid).unwrap().collection()) else { return };
+  match turn {
+    TileTurn::Rotate => tile.rotate(),
+    TileTurn::Flip => tile.flip(),
+  }
+}
+
+/// R rotates the dragged tile, F flips it
+fn turn_dragged_tile(
+  mut commands: Commands,
+  keys: Res<ButtonInput<KeyCode>>,
+  dragged: Query<Entity, With<DraggedTile>>,
+) {
+  let turn = if keys.just_pressed(KeyCode::KeyR) {
+    TileTurn::Rotate
+  } else if keys.just_pressed(KeyCode::KeyF) {
+    TileTurn::Flip
+  } else {
+    return;
+  };
+  for grid in dragged {
+    commands.run_system_cached_with(turn_tile, (grid, turn));
+  }
+}
 
-fn on_release(tile: Entity) -> impl Fn(Trigger<Pointer<Released>>) -> Entity {move |
-  _trigger: Trigger<Pointer<Released>>,
-| {
-  tile
-}}
+/// Once the faces of a turned tile are laid out again, the overlap preview is computed from their new positions
+fn refresh_turned_tile_overlap(
+  mut commands: Commands,
+  turned: Query<Entity, (With<DraggedTile>, Changed<Children>)>,
+) {
+  for grid in turned {
+    commands.run_system_cached_with(overlap_tile_template.pipe(mark_faces), grid);
+  }
+}
 
 struct OverlapTileTemplateOutput {
   grid: Entity,
@@ -460,6 +750,7 @@ fn overlap_tile_template(
   my_team: Single<&Children, With<MyTeam>>,
   overlap_indicators: Query<Entity, With<OverlapIndicator>>,
   computed: Query<&ComputedNode>,
+  (tile_grids, tiles, face_sources, faces): (Query<&DiceGridOf>, Query<&Tile>, Query<&FaceSource>, Query<&Face>),
 ) -> OverlapTileTemplateOutput {
   let grid = *grid;
   // Cleanup
@@ -491,6 +782,16 @@ fn overlap_tile_template(
     valid = false;
   }
 
+  // Each kind of tile decides which faces it can go on
+  let tile = tiles.get(tile_grids.get(grid).unwrap().collection()).unwrap();
+  let accepted = matches.iter().all(|(_, (_, template_face_node))| {
+    let template_face = face_sources.get(*template_face_node).unwrap().source();
+    tile.accepts(faces.get(template_face).unwrap().prototype)
+  });
+  if !accepted {
+    valid = false;
+  }
+
   let templates: Vec<Entity> = matches
     .iter()
     .map(|(_, (template, _))| *template)
@@ -548,7 +849,8 @@ fn apply_tile(
 ) {
   let In(OverlapTileTemplateOutput { grid, matched, matches }) = input;
   let tile = grids.get(grid).unwrap().collection();
-  let price = tiles.get(tile).unwrap().price();
+  let tile_kind = tiles.get(tile).unwrap();
+  let price = tile_kind.price();
   if !matched || **coins < price {
     let mut node = nodes.get_mut(grid).unwrap();
     node.position_type = PositionType::Relative;
@@ -562,10 +864,9 @@ fn apply_tile(
     let tile_face = face_sources.get(tile_node).unwrap().source();
     let template_face = face_sources.get(template_node).unwrap().source();
 
-    let tile_face = faces.get(tile_face).unwrap().clone();
+    let tile_face = faces.get(tile_face).unwrap().prototype;
     let mut template_face = faces.get_mut(template_face).unwrap();
-    template_face.prototype.action = tile_face.prototype.action;
-    template_face.prototype.pips = tile_face.prototype.pips;
+    tile_kind.apply(tile_face, &mut template_face.prototype);
   }
   commands.entity(tile).despawn();
 }
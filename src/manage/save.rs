@@ -0,0 +1,210 @@
+use std::error::Error;
+
+use bevy::{ecs::relationship::RelatedSpawnerCommands, prelude::*};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  constants::SAVE_PATH,
+  dice::{DiceClasses, DiceTemplate, DiceTemplateBuilder, Face, FacePrototype, Health},
+  manage::{economy::{Losses, Streak}, plugin::{Coins, Locked, MyTeam, RestoredShop, Shop, ShopRound}, item::Item, tile::Tile},
+  states::GameState,
+  utils::{rng::GameRng, ron_asset::RonError},
+};
+
+/// Snapshot of a run taken when entering the manage screen
+#[derive(Serialize, Deserialize)]
+pub struct RunSave {
+  pub team: Vec<DiceTemplateBuilder>,
+  pub coins: u32,
+  pub shop_round: u32,
+  pub shop: Vec<Option<SavedShopItem>>,
+  /// Indices of the locked shop spots
+  #[serde(default)]
+  pub locked_spots: Vec<usize>,
+  #[serde(default)]
+  pub streak: Streak,
+  #[serde(default)]
+  pub losses: u32,
+  pub seed: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum SavedShopItem {
+  Tile(SavedTile),
+  Dice(DiceTemplateBuilder),
+  Item(Item),
+}
+
+impl SavedShopItem {
+  pub fn spawn(self, images: &mut Assets<Image>, commands: &mut RelatedSpawnerCommands<ChildOf>) {
+    match self {
+      Self::Tile(SavedTile { tile, faces }) => tile.spawn_with_faces(faces, images, commands),
+      Self::Dice(builder) => builder.spawn(commands, images),
+      Self::Item(item) => item.spawn(commands),
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedTile {
+  pub tile: Tile,
+  pub faces: Vec<FacePrototype>,
+}
+
+impl RunSave {
+  pub fn save(&self) -> Result<(), Box<dyn Error>> {
+    let text = ron::ser::to_string_pretty(self, PrettyConfig::default())?;
+    std::fs::write(SAVE_PATH, text)?;
+    Ok(())
+  }
+
+  pub fn load() -> Result<Self, Box<dyn Error>> {
+    let text = std::fs::read_to_string(SAVE_PATH)?;
+    Ok(ron::from_str(&text)?)
+  }
+
+  /// Rejects dices of classes or levels that are not in the class files anymore
+  pub fn validate_classes(&self, classes: &DiceClasses) -> Result<(), RonError> {
+    let shop_dices = self.shop.iter().filter_map(|item| match item {
+      Some(SavedShopItem::Dice(builder)) => Some(builder),
+      _ => None,
+    });
+    for builder in self.team.iter().chain(shop_dices) {
+      builder.validate_class(classes)?;
+    }
+    Ok(())
+  }
+
+  pub fn exists() -> bool {
+    std::path::Path::new(SAVE_PATH).exists()
+  }
+
+  /// Called when the run ends, so it cannot be continued
+  pub fn delete() {
+    let _ = std::fs::remove_file(SAVE_PATH);
+  }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn save_run(
+  my_team: Single<&Children, With<MyTeam>>,
+  shop: Single<&Children, With<Shop>>,
+  templates: Query<(&DiceTemplate, &Children, &Health)>,
+  shop_spots: Query<&Children>,
+  locked: Query<(), With<Locked>>,
+  tiles: Query<(&Tile, &Children)>,
+  items: Query<&Item>,
+  faces: Query<&Face>,
+  coins: Res<Coins>,
+  shop_round: Res<ShopRound>,
+  rng: Res<GameRng>,
+  (streak, losses): (Res<Streak>, Res<Losses>),
+) {
+  let team = my_team
+    .iter()
+    .map(|template| {
+      let (dice_template, template_faces, health) = templates.get(template).unwrap();
+      DiceTemplateBuilder::from_template(dice_template, template_faces, health, &faces)
+    })
+    .collect();
+
+  let locked_spots = shop
+    .iter()
+    .enumerate()
+    .filter(|&(_, spot)| locked.contains(spot))
+    .map(|(index, _)| index)
+    .collect();
+
+  let shop = shop
+    .iter()
+    .map(|spot| {
+      let item = shop_spots.get(spot).ok()?.first().copied()?;
+      if let Ok((dice_template, template_faces, health)) = templates.get(item) {
+        return Some(SavedShopItem::Dice(DiceTemplateBuilder::from_template(dice_template, template_faces, health, &faces)));
+      }
+      if let Ok(&shop_item) = items.get(item) {
+        return Some(SavedShopItem::Item(shop_item));
+      }
+      let (tile, tile_faces) = tiles.get(item).ok()?;
+      Some(SavedShopItem::Tile(SavedTile {
+        tile: tile.clone(),
+        faces: tile_faces.iter().map(|face| faces.get(face).unwrap().prototype).collect(),
+      }))
+    })
+    .collect();
+
+  let save = RunSave {
+    team,
+    coins: **coins,
+    shop_round: **shop_round,
+    shop,
+    locked_spots,
+    streak: *streak,
+    losses: **losses,
+    seed: rng.seed(),
+  };
+  if let Err(error) = save.save() {
+    warn!("Failed to save the run: {error}");
+  }
+}
+
+pub fn continue_run(
+  mut commands: Commands,
+  mut images: ResMut<Assets<Image>>,
+  mut coins: ResMut<Coins>,
+  mut shop_round: ResMut<ShopRound>,
+  mut rng: ResMut<GameRng>,
+  mut game_state: ResMut<NextState<GameState>>,
+  classes: Res<DiceClasses>,
+) {
+  let save = match RunSave::load() {
+    Ok(save) => save,
+    Err(error) => {
+      warn!("Failed to load the run: {error}");
+      return;
+    }
+  };
+  if let Err(error) = save.validate_classes(&classes) {
+    warn!("Failed to load the run: {error}");
+    return;
+  }
+
+  **coins = save.coins;
+  **shop_round = save.shop_round;
+  *rng = GameRng::new(save.seed);
+  commands.insert_resource(save.streak);
+  commands.insert_resource(Losses::new(save.losses));
+
+  commands.spawn((
+    Name::new("My team"),
+    MyTeam,
+  )).with_children(|commands| {
+    for builder in save.team {
+      builder.spawn(commands, &mut images);
+    }
+  });
+
+  commands.spawn((
+    Name::new("Shop"),
+    Shop,
+    RestoredShop,
+  )).with_children(|commands| {
+    for (index, saved_item) in save.shop.into_iter().enumerate() {
+      let mut spot = commands.spawn((
+        Name::new("Shop spot"),
+      ));
+      if save.locked_spots.contains(&index) {
+        spot.insert(Locked);
+      }
+      spot
+        .with_children(|commands| {
+          if let Some(saved_item) = saved_item {
+            saved_item.spawn(&mut images, commands);
+          }
+        });
+    }
+  });
+
+  game_state.set(GameState::Manage);
+}
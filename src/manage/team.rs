@@ -0,0 +1,205 @@
+use bevy::{ecs::relationship::RelatedSpawnerCommands, prelude::*};
+
+use crate::{
+  battle::Campaign,
+  constants::DICE_SIZE,
+  dice::{DiceTemplate, HealthBar},
+  manage::{dice_grid::DiceGrid, plugin::{Coins, MyTeam}, tile::{Buyable, PriceTag}},
+  states::GameState,
+};
+
+pub struct TeamPlugin;
+
+impl Plugin for TeamPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .add_systems(Update, (update_team_display, update_level_displays).run_if(in_state(GameState::Manage)));
+  }
+}
+
+impl Buyable for DiceTemplate {
+  fn price(&self) -> u32 {
+    3 + self.level
+  }
+}
+
+/// Lists the dices of `MyTeam`, respawned whenever the team changes
+#[derive(Component)]
+pub struct TeamDisplay;
+
+/// Dropping a dice of the team here sells it
+#[derive(Component)]
+pub struct SellArea;
+
+/// Spot of a dice of `MyTeam` in the `TeamDisplay`
+#[derive(Component)]
+pub struct TeamSpot(pub Entity);
+
+/// Shows the level of a dice template
+#[derive(Component)]
+struct LevelDisplay(Entity);
+
+pub fn spawn_template_spot<'a>(commands: &'a mut RelatedSpawnerCommands<ChildOf>, template: Entity) -> EntityCommands<'a> {
+  let mut spot = commands.spawn((
+    Name::new("Dice template spot"),
+    Node::default(),
+  ));
+  spot.with_children(|commands| {
+    DiceGrid::spawn(commands, template);
+    commands.spawn((
+      Name::new("Level display"),
+      Node {
+        position_type: PositionType::Absolute,
+        top: Val::Px(0.0),
+        left: Val::Px(0.0),
+        ..default()
+      },
+      Text::new(""),
+      TextColor(Color::BLACK),
+      LevelDisplay(template),
+      Pickable::IGNORE,
+    ));
+    commands.spawn((
+      Name::new("Health bar container"),
+      Node {
+        position_type: PositionType::Absolute,
+        bottom: Val::Px(-0.2 * DICE_SIZE),
+        margin: UiRect { left: Val::Auto, right: Val::Auto, ..default() },
+        ..default()
+      },
+    )).with_children(|commands| {
+      HealthBar::spawn(commands, template);
+    });
+  });
+  spot
+}
+
+fn update_team_display(
+  mut commands: Commands,
+  my_team: Single<Ref<Children>, With<MyTeam>>,
+  display: Single<(Entity, Ref<TeamDisplay>)>,
+) {
+  let (display, marker) = display.into_inner();
+  if !my_team.is_changed() && !marker.is_added() { return; }
+
+  commands
+    .entity(display)
+    .despawn_related::<Children>()
+    .with_children(|commands| {
+      for template in my_team.iter() {
+        spawn_template_spot(commands, template)
+          .insert(TeamSpot(template))
+          .observe(drag_team_spot)
+          .observe(drop_team_spot);
+      }
+    });
+}
+
+fn update_level_displays(
+  displays: Query<(&mut Text, Ref<LevelDisplay>)>,
+  templates: Query<Ref<DiceTemplate>>,
+) {
+  for (mut text, display) in displays {
+    let Ok(template) = templates.get(display.0) else { continue };
+    if display.is_added() || template.is_changed() {
+      text.0 = format!("Lv {}", template.level);
+    }
+  }
+}
+
+fn drag_team_spot(
+  drag: Trigger<Pointer<Drag>>,
+  mut nodes: Query<(&mut Node, &ChildOf)>,
+  layout: Query<(&GlobalTransform, &ComputedNode)>,
+) {
+  // Top left corner of the node, in logical pixels
+  let corner = |entity| {
+    let (transform, node) = layout.get(entity).unwrap();
+    (transform.translation().truncate() - node.size / 2.0) * node.inverse_scale_factor
+  };
+  let spot = drag.target();
+  let (mut node, &ChildOf(parent)) = nodes.get_mut(spot).unwrap();
+  let delta = drag.delta;
+  match (node.left, node.top) {
+    (Val::Px(x), Val::Px(y)) => {
+      node.left = Val::Px(x + delta.x);
+      node.top = Val::Px(y + delta.y);
+    },
+    (_, _) => {
+      let Vec2 { x, y } = corner(spot) - corner(parent);
+      node.left = Val::Px(x + delta.x);
+      node.top = Val::Px(y + delta.y);
+    },
+  }
+  node.position_type = PositionType::Absolute;
+}
+
+/// Sells the dice when dropped on the `SellArea`, otherwise moves it to the place of the closest spot
+#[allow(clippy::too_many_arguments)]
+fn drop_team_spot(
+  drag_end: Trigger<Pointer<DragEnd>>,
+  mut commands: Commands,
+  mut nodes: Query<&mut Node>,
+  spots: Query<(Entity, &TeamSpot, &GlobalTransform, &ComputedNode)>,
+  sell_area: Single<(&GlobalTransform, &ComputedNode), With<SellArea>>,
+  my_team: Single<(Entity, &Children), With<MyTeam>>,
+  templates: Query<&DiceTemplate>,
+  mut coins: ResMut<Coins>,
+) {
+  let spot = drag_end.target();
+  let Ok((_, &TeamSpot(template), transform, _)) = spots.get(spot) else { return };
+  let position = transform.translation().truncate();
+  let (my_team, team) = *my_team;
+
+  let (sell_transform, sell_node) = *sell_area;
+  let sell_rect = Rect::from_center_size(sell_transform.translation().truncate(), sell_node.size);
+  if sell_rect.contains(position) && team.len() > 1 {
+    // Dices sell back for half their price
+    **coins += templates.get(template).unwrap().price() / 2;
+    commands.entity(template).despawn();
+    return;
+  }
+
+  let closest = spots
+    .iter()
+    .filter(|&(other, ..)| other != spot)
+    .filter(|(_, _, other_transform, other_node)| {
+      other_transform.translation().truncate().distance(position) < other_node.size.x / 2.0
+    })
+    .min_by(|(_, _, a, _), (_, _, b, _)| {
+      a.translation().truncate().distance(position).total_cmp(&b.translation().truncate().distance(position))
+    });
+  match closest.and_then(|(_, TeamSpot(other), ..)| team.iter().position(|entity| entity == *other)) {
+    Some(index) => {
+      commands.entity(my_team).insert_children(index, &[template]);
+    },
+    None => {
+      let mut node = nodes.get_mut(spot).unwrap();
+      node.position_type = PositionType::Relative;
+      node.left = Val::Auto;
+      node.top = Val::Auto;
+    },
+  }
+}
+
+/// Moves the dice offered by the shop to the team, if it has room for it
+pub fn buy_dice(
+  In(template): In<Entity>,
+  mut commands: Commands,
+  my_team: Single<(Entity, &Children), With<MyTeam>>,
+  templates: Query<(&DiceTemplate, &DiceGrid)>,
+  campaign: Res<Campaign>,
+  mut coins: ResMut<Coins>,
+) {
+  let (my_team, team) = *my_team;
+  let Ok((dice_template, grid)) = templates.get(template) else { return };
+  let price = dice_template.price();
+  if **coins < price || team.len() >= campaign.max_team_size {
+    return;
+  }
+  **coins -= price;
+
+  commands.entity(grid.grid()).despawn();
+  commands.entity(template).despawn_related::<PriceTag>();
+  commands.entity(my_team).add_child(template);
+}
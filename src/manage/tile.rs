@@ -1,11 +1,87 @@
 use bevy::{ecs::relationship::RelatedSpawnerCommands, prelude::*};
-use rand::{seq::SliceRandom, thread_rng};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
 
-use crate::dice::{face_prototypes::*, Face, FacePrototype, Gridable};
+use crate::{dice::{face_prototypes::*, Action, FaceBackground, Face, FacePrototype, Gridable}};
 
-#[derive(Component, Clone)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Tile {
   grid: Vec<(i16, i16)>,
+  #[serde(default)]
+  kind: TileKind,
+  #[serde(default)]
+  rarity: Rarity,
+  /// Computed from the faces when the tile is spawned
+  #[serde(skip)]
+  price: u32,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TileKind {
+  /// Replaces the action and pips of the faces it is dropped on, and their background when the tile face has one
+  #[default]
+  Faces,
+  /// Sticker replacing only the background of a face that has an action
+  Background,
+}
+
+/// Rarer tiles carry stronger faces and show up more often in later shop rounds
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Rarity {
+  #[default]
+  Common,
+  Rare,
+  Epic,
+}
+
+impl Rarity {
+  pub fn color(&self) -> Color {
+    match self {
+      Self::Common => Color::linear_rgb(0.5, 0.5, 0.5),
+      Self::Rare => Color::linear_rgb(0.1, 0.3, 0.9),
+      Self::Epic => Color::linear_rgb(0.6, 0.1, 0.8),
+    }
+  }
+
+  /// Rolls a rarity, rare and epic tiles getting more likely as the shop rounds go by
+  fn random(rng: &mut StdRng, shop_round: u32) -> Self {
+    let weights = [
+      (Self::Common, 8),
+      (Self::Rare, 2 + shop_round),
+      (Self::Epic, shop_round.saturating_sub(2)),
+    ];
+    weights.choose_weighted(rng, |&(_, weight)| weight).unwrap().0
+  }
+
+  fn faces(&self) -> &'static [FacePrototype] {
+    match self {
+      Self::Common => &[
+        ATTACK_WEAK,
+        DEFEND,
+        FIRE_WEAK,
+        REGEN_WEAK,
+        BLOCK_WEAK,
+        POISON_WEAK,
+      ],
+      Self::Rare => &[
+        ATTACK_STRONG,
+        FIRE_STRONG,
+        REGEN_STRONG,
+        FIERY,
+        BLOCK_STRONG,
+        FREEZE,
+        CLEANSE,
+        ATTACK_STRONG_CRUEL,
+        POISON_WEAK_CRUEL,
+      ],
+      Self::Epic => &[
+        ATTACK_STRONG_DOUBLE,
+        FIRE_STRONG_DOUBLE,
+        REGEN_STRONG_DOUBLE,
+        BLOCK_STRONG_DOUBLE,
+      ],
+    }
+  }
 }
 
 pub trait Buyable {
@@ -14,10 +90,35 @@ pub trait Buyable {
 
 impl Buyable for Tile {
   fn price(&self) -> u32 {
-    2
+    self.price
   }
 }
 
+/// Shows the price of a shop item on its shop spot, despawned with the item
+#[derive(Component)]
+#[relationship(relationship_target = PriceTag)]
+pub struct PriceTagOf(Entity);
+
+#[derive(Component)]
+#[relationship_target(relationship = PriceTagOf, linked_spawn)]
+pub struct PriceTag(Entity);
+
+pub fn spawn_price_tag(commands: &mut RelatedSpawnerCommands<ChildOf>, item: Entity, price: u32) {
+  commands.spawn((
+    Name::new("Price tag"),
+    Node {
+      position_type: PositionType::Absolute,
+      right: Val::Px(0.0),
+      bottom: Val::Px(0.0),
+      ..default()
+    },
+    Text::new(format!("{price}")),
+    TextColor(Color::BLACK),
+    PriceTagOf(item),
+    Pickable::IGNORE,
+  ));
+}
+
 impl Gridable for Tile {
   fn grid(&self) -> Vec<(i16, i16)> {
     self.grid.clone()
@@ -25,51 +126,124 @@ impl Gridable for Tile {
 }
 
 impl Tile {
-  pub fn spawn(images: &mut Assets<Image>, commands: &mut RelatedSpawnerCommands<ChildOf>) {
-    let grid = build_tile_layout();
-    let faces_count = grid.len();
-    commands.spawn(Self { grid })
+  /// A tile of faces and the faces to spawn it with
+  pub fn random(rng: &mut StdRng, shop_round: u32) -> (Self, Vec<FacePrototype>) {
+    let rarity = Rarity::random(rng, shop_round);
+    let grid = build_tile_layout(rng);
+    let faces = grid.iter().map(|_| *rarity.faces().choose(rng).unwrap()).collect();
+    (Self { grid, kind: TileKind::Faces, rarity, price: 0 }, faces)
+  }
+
+  pub fn random_sticker(rng: &mut StdRng) -> (Self, Vec<FacePrototype>) {
+    let background = *[FaceBackground::Cruel, FaceBackground::Double].choose(rng).unwrap();
+    let face = FacePrototype { background, ..FacePrototype::new(Action::Empty, None) };
+    let rarity = if background == FaceBackground::Double { Rarity::Epic } else { Rarity::Rare };
+    (Self { grid: vec![(1, 1)], kind: TileKind::Background, rarity, price: 0 }, vec![face])
+  }
+
+  pub fn rarity(&self) -> Rarity {
+    self.rarity
+  }
+
+  /// Whether the tile face can be applied on the template face
+  pub fn accepts(&self, template_face: FacePrototype) -> bool {
+    match self.kind {
+      TileKind::Faces => true,
+      TileKind::Background => template_face.action != Action::Empty,
+    }
+  }
+
+  /// Turns the tile a quarter turn clockwise
+  pub fn rotate(&mut self) {
+    rotate_grid(&mut self.grid);
+  }
+
+  /// Mirrors the tile horizontally
+  pub fn flip(&mut self) {
+    for (x, _) in self.grid.iter_mut() {
+      *x = -*x;
+    }
+    normalize_grid(&mut self.grid);
+  }
+
+  pub fn apply(&self, tile_face: FacePrototype, template_face: &mut FacePrototype) {
+    match self.kind {
+      TileKind::Faces => {
+        template_face.action = tile_face.action;
+        template_face.pips = tile_face.pips;
+        // Rare faces carry their background, plain faces keep the one of the template
+        if tile_face.background != FaceBackground::Empty {
+          template_face.background = tile_face.background;
+        }
+      },
+      TileKind::Background => {
+        template_face.background = tile_face.background;
+      },
+    }
+  }
+
+  pub fn spawn_with_faces(mut self, faces: Vec<FacePrototype>, images: &mut Assets<Image>, commands: &mut RelatedSpawnerCommands<ChildOf>) {
+    self.price = price_of(&faces);
+    commands.spawn(self)
       .with_children(|commands|{
-        for _ in 0..faces_count {
-          let prototype = random_face();
+        for prototype in faces {
           Face::from_prototype(prototype, images).spawn(commands);
         }
     });
   }
 }
 
-fn random_face() -> FacePrototype {
-  *[
-    ATTACK_STRONG,
-    ATTACK_WEAK,
-    DEFEND,
-    FIRE_WEAK,
-    FIRE_STRONG,
-    REGEN_WEAK,
-    REGEN_STRONG,
-    FIERY,
-  ].choose(&mut thread_rng()).unwrap()
+/// Bigger tiles and stronger faces cost more
+fn price_of(faces: &[FacePrototype]) -> u32 {
+  let power: u32 = faces
+    .iter()
+    .map(|face| {
+      let action = if face.action == Action::Empty { 0 } else { face.pips.unwrap_or(1) };
+      let background = if face.background == FaceBackground::Empty { 0 } else { 4 };
+      action + background
+    })
+    .sum();
+  1 + power / 2
 }
 
-fn build_tile_layout() -> Vec<(i16, i16)> {
-  match *[
-    "T1", "T2", "T3I", "T3L"
-  ].choose(&mut thread_rng()).unwrap() {
-    "T1" => vec![vec![(1, 1)]],
-    "T2" => vec![
-      vec![(1, 1), (1, 2)],
-      vec![(1, 1), (2, 1)],
-    ],
-    "T3I" => vec![
-      vec![(1, 1), (1, 2), (1, 3)],
-      vec![(1, 1), (2, 1), (3, 1)],
-    ],
-    "T3L" => vec![
-      vec![(1, 1), (1, 2), (2, 2)],
-      vec![(1, 1), (2, 1), (2, 2)],
-      vec![(1, 2), (1, 1), (1, 2)],
-      vec![(1, 2), (2, 2), (1, 2)],
-    ],
-    _ => panic!(),
-  }.choose(&mut thread_rng()).unwrap().clone()
+fn rotate_grid(grid: &mut [(i16, i16)]) {
+  for (x, y) in grid.iter_mut() {
+    (*x, *y) = (-*y, *x);
+  }
+  normalize_grid(grid);
+}
+
+/// Moves the grid back so that it starts at (1, 1), like the grid placements of the UI
+fn normalize_grid(grid: &mut [(i16, i16)]) {
+  let min_x = grid.iter().map(|&(x, _)| x).min().unwrap_or(1);
+  let min_y = grid.iter().map(|&(_, y)| y).min().unwrap_or(1);
+  for (x, y) in grid.iter_mut() {
+    *x += 1 - min_x;
+    *y += 1 - min_y;
+  }
+}
+
+/// Layouts offered by the shop with their weights, bigger tiles being rarer
+const TILE_LAYOUTS: &[(&[(i16, i16)], u32)] = &[
+  (&[(1, 1)], 3),
+  (&[(1, 1), (1, 2)], 3),
+  (&[(1, 1), (1, 2), (1, 3)], 2),
+  (&[(1, 1), (1, 2), (2, 2)], 2),
+  // Square, T, S and Z tetrominoes
+  (&[(1, 1), (2, 1), (1, 2), (2, 2)], 1),
+  (&[(1, 1), (2, 1), (3, 1), (2, 2)], 1),
+  (&[(2, 1), (3, 1), (1, 2), (2, 2)], 1),
+  (&[(1, 1), (2, 1), (2, 2), (3, 2)], 1),
+  // The whole net of a dice, as laid out by `DiceTemplate::grid`
+  (&[(2, 1), (1, 2), (3, 2), (2, 2), (2, 3), (2, 4)], 1),
+];
+
+fn build_tile_layout(rng: &mut StdRng) -> Vec<(i16, i16)> {
+  let (layout, _) = TILE_LAYOUTS.choose_weighted(rng, |&(_, weight)| weight).unwrap();
+  let mut grid = layout.to_vec();
+  // Players can rotate tiles while dragging them, the shop only picks a starting orientation
+  for _ in 0..rng.gen_range(0..4) {
+    rotate_grid(&mut grid);
+  }
+  grid
 }
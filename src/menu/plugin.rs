@@ -1,29 +1,83 @@
-use bevy::{prelude::*, app::AppExit, ui::Interaction};
-use crate::{dice::DiceTemplateBuilder, manage::plugin::{Coins, MyTeam, ShopRound}, states::GameState};
+use bevy::{prelude::*, app::AppExit, input::{keyboard::{Key, KeyboardInput}, ButtonState}, ui::Interaction};
+use crate::{battle::replay::start_replay, dice::{DiceClasses, DiceTemplateBuilder}, constants::{STARTING_CLASSES, STARTING_COINS}, manage::{economy::{Losses, Streak}, plugin::{Coins, MyTeam, ShopRound}, save::{continue_run, RunSave}}, states::GameState, utils::{rng::{FixedSeed, GameRng}, ron_asset::RonError}};
 
 pub struct MenuPlugin;
 
 impl Plugin for MenuPlugin {
   fn build(&self, app: &mut App) {
     app
-      .add_systems(OnEnter(GameState::Menu), spawn_menu)
+      .add_systems(OnEnter(GameState::Menu), (roll_seed.run_if(not(resource_exists::<FixedSeed>)), spawn_menu).chain())
       .add_systems(OnExit(GameState::Menu), despawn_menu)
-      .add_systems(Update, button_actions.run_if(in_state(GameState::Menu)));
+      .add_systems(Update, (button_actions, edit_seed).run_if(in_state(GameState::Menu)));
   }
 }
 
 #[derive(Component)]
 enum ButtonAction {
+    Continue,
     Play,
+    Replay,
     Quit,
 }
 
 #[derive(Component)]
 struct MenuScreen;
 
+#[derive(Component)]
+struct SeedDisplay;
+
+fn seed_text(seed: u64) -> String {
+  format!("Seed: {seed} (type to change)")
+}
+
+fn roll_seed(
+  mut rng: ResMut<GameRng>,
+) {
+  *rng = GameRng::default();
+}
+
+/// Digits typed in the menu, replacing the seed rolled on entering it while not empty
+#[derive(Resource)]
+struct SeedInput {
+  digits: String,
+  rolled_seed: u64,
+}
+
+fn edit_seed(
+  mut keyboard: EventReader<KeyboardInput>,
+  mut input: ResMut<SeedInput>,
+  mut rng: ResMut<GameRng>,
+  display: Single<&mut Text, With<SeedDisplay>>,
+) {
+  let mut changed = false;
+  for event in keyboard.read() {
+    if event.state != ButtonState::Pressed { continue; }
+    match &event.logical_key {
+      Key::Character(character) => {
+        let digits: String = character.chars().filter(char::is_ascii_digit).collect();
+        let typed = format!("{}{digits}", input.digits);
+        // Digits that would not fit in a seed are ignored
+        if !digits.is_empty() && typed.parse::<u64>().is_ok() {
+          input.digits = typed;
+          changed = true;
+        }
+      }
+      Key::Backspace => changed |= input.digits.pop().is_some(),
+      _ => {}
+    }
+  }
+  if !changed { return; }
+
+  let seed = input.digits.parse().unwrap_or(input.rolled_seed);
+  *rng = GameRng::new(seed);
+  *display.into_inner() = Text::new(seed_text(seed));
+}
+
 fn spawn_menu(
   mut commands: Commands,
+  rng: Res<GameRng>,
 ) {
+  commands.insert_resource(SeedInput { digits: String::new(), rolled_seed: rng.seed() });
   commands.spawn((
     Name::new("Main Menu"),
     Node {
@@ -48,6 +102,30 @@ fn spawn_menu(
         TextColor(Color::srgb(0.0, 0.0, 0.0)),
     ));
 
+      commands.spawn((
+        Name::new("Seed display"),
+        Text::new(seed_text(rng.seed())),
+        TextFont { font_size: 30.0, ..default() },
+        TextColor(Color::srgb(0.0, 0.0, 0.0)),
+        SeedDisplay,
+      ));
+
+      if RunSave::exists() {
+        commands.spawn((
+          Name::new("Continue button"),
+          Button,
+          Node { align_items: AlignItems::Center, justify_content: JustifyContent::Center, width: Val::Percent(10.0), height: Val::Percent(10.0), ..default() },
+          BackgroundColor(Color::srgb(0.5, 0.0, 0.0)),
+          ButtonAction::Continue,
+        )).with_children(|commands| {
+          commands.spawn((
+            Text("Continue".to_string()),
+            TextFont { font_size: 50.0, ..default() },
+            TextColor(Color::srgb(0.0, 0.0, 0.0)),
+          ));
+        });
+      }
+
       commands.spawn((
         Name::new("Play button"),
         Button,
@@ -62,6 +140,20 @@ fn spawn_menu(
         ));
       });
 
+      commands.spawn((
+        Name::new("Replay button"),
+        Button,
+        Node { align_items: AlignItems::Center, justify_content: JustifyContent::Center, width: Val::Percent(10.0), height: Val::Percent(10.0), ..default() },
+        BackgroundColor(Color::srgb(0.5, 0.0, 0.0)),
+        ButtonAction::Replay,
+      )).with_children(|commands| {
+        commands.spawn((
+          Text("Replay".to_string()),
+          TextFont { font_size: 50.0, ..default() },
+          TextColor(Color::srgb(0.0, 0.0, 0.0)),
+        ));
+      });
+
       commands.spawn((
         Name::new("Quit button"),
         Button,
@@ -97,9 +189,15 @@ fn button_actions(
     }
 
     match button_action {
+      ButtonAction::Continue => {
+        commands.run_system_cached(continue_run);
+      }
       ButtonAction::Play => {
         commands.run_system_cached(new_game);
       }
+      ButtonAction::Replay => {
+        commands.run_system_cached(start_replay);
+      }
       ButtonAction::Quit => { app_exit_events.write(AppExit::Success); }
     }
   }
@@ -111,22 +209,38 @@ fn new_game(
   mut commands: Commands,
   mut images: ResMut<Assets<Image>>,
   mut game_state: ResMut<NextState<GameState>>,
+  mut rng: ResMut<GameRng>,
+  classes: Res<DiceClasses>,
 ) {
-  **coins = 5;
+  let team = match starting_team(&classes) {
+    Ok(team) => team,
+    Err(error) => {
+      warn!("Failed to start a run: {error}");
+      return;
+    }
+  };
+  rng.restart();
+  **coins = STARTING_COINS;
   **shop_round = 1;
+  commands.insert_resource(Streak::default());
+  commands.insert_resource(Losses::default());
   commands.spawn((
     Name::new("My team"),
     MyTeam,
   )).with_children(|commands| {
-    for builder in [
-      DiceTemplateBuilder::berserker(1),
-      DiceTemplateBuilder::paladin(1),
-      DiceTemplateBuilder::mage(1),
-      DiceTemplateBuilder::cleric(1),
-      DiceTemplateBuilder::rogue(1),
-    ] {
+    for builder in team {
       builder.spawn(commands, &mut images);
     }
   });
   game_state.set(GameState::Manage);
 }
+
+pub fn starting_team(classes: &DiceClasses) -> Result<Vec<DiceTemplateBuilder>, RonError> {
+  STARTING_CLASSES
+    .iter()
+    .map(|&class| {
+      let class = classes.get(class).ok_or_else(|| format!("Unknown starting class {class}"))?;
+      Ok(DiceTemplateBuilder::from_class(class, 1))
+    })
+    .collect()
+}
@@ -6,5 +6,18 @@ pub enum GameState {
     Loading,
     Menu,
     Battle,
+    Replay,
     Manage,
 }
+
+/// Active both for a live battle and for a replayed one
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct InBattle;
+
+impl ComputedStates for InBattle {
+    type SourceStates = GameState;
+
+    fn compute(state: GameState) -> Option<Self> {
+        matches!(state, GameState::Battle | GameState::Replay).then_some(InBattle)
+    }
+}
@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Present when the seed was given on the command line, so it is kept across runs
+#[derive(Resource)]
+pub struct FixedSeed;
+
+/// Every random decision of a run goes through one of these streams,
+/// so the same seed reproduces the same shops, targets and throws.
+#[derive(Resource)]
+pub struct GameRng {
+  seed: u64,
+  shop: StdRng,
+  targeting: StdRng,
+  roll: StdRng,
+}
+
+impl Default for GameRng {
+  fn default() -> Self {
+    Self::new(rand::random())
+  }
+}
+
+impl GameRng {
+  pub fn new(seed: u64) -> Self {
+    Self::for_round(seed, 0, 0)
+  }
+
+  fn for_round(seed: u64, round: u32, attempt: u32) -> Self {
+    let round_seed = seed
+      .wrapping_add((round as u64) << 32)
+      .wrapping_add((attempt as u64) << 48);
+    Self {
+      seed,
+      shop: StdRng::seed_from_u64(round_seed),
+      targeting: StdRng::seed_from_u64(round_seed.wrapping_add(1)),
+      roll: StdRng::seed_from_u64(round_seed.wrapping_add(2)),
+    }
+  }
+
+  pub fn seed(&self) -> u64 {
+    self.seed
+  }
+
+  /// Rewinds all streams to the start of the seed
+  pub fn restart(&mut self) {
+    *self = Self::new(self.seed);
+  }
+
+  /// Reseeds all streams for the given shop round, so a resumed run continues exactly like the original
+  /// once `spawn_shop` has replayed the draws of the stock it was saved with.
+  /// The attempt counts the battles lost so far, so retrying a round after a loss rolls a new shop
+  pub fn start_round(&mut self, round: u32, attempt: u32) {
+    *self = Self::for_round(self.seed, round, attempt);
+  }
+
+  pub fn shop(&mut self) -> &mut StdRng {
+    &mut self.shop
+  }
+
+  pub fn targeting(&mut self) -> &mut StdRng {
+    &mut self.targeting
+  }
+
+  pub fn roll(&mut self) -> &mut StdRng {
+    &mut self.roll
+  }
+}
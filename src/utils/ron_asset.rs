@@ -0,0 +1,68 @@
+use std::{error::Error, marker::PhantomData, path::Path};
+
+use bevy::{asset::{io::Reader, AssetLoader, LoadContext}, prelude::*};
+use serde::de::DeserializeOwned;
+
+pub type RonError = Box<dyn Error + Send + Sync>;
+
+/// An asset written by hand in a RON file
+pub trait RonAsset: Asset + DeserializeOwned {
+  const EXTENSIONS: &'static [&'static str];
+
+  /// Rejects files that deserialize but break the rules of the asset
+  fn validate(&self) -> Result<(), RonError> {
+    Ok(())
+  }
+
+  fn parse(bytes: &[u8]) -> Result<Self, RonError> {
+    let asset: Self = ron::de::from_bytes(bytes)?;
+    asset.validate()?;
+    Ok(asset)
+  }
+
+  /// Reads the file straight from disk, for when there is no app to load it
+  fn read(path: &Path) -> Result<Self, RonError> {
+    Self::parse(&std::fs::read(path)?)
+  }
+}
+
+struct RonAssetLoader<A>(PhantomData<A>);
+
+impl<A> Default for RonAssetLoader<A> {
+  fn default() -> Self {
+    Self(PhantomData)
+  }
+}
+
+impl<A: RonAsset> AssetLoader for RonAssetLoader<A> {
+  type Asset = A;
+  type Settings = ();
+  type Error = RonError;
+
+  async fn load(
+    &self,
+    reader: &mut dyn Reader,
+    _settings: &(),
+    _load_context: &mut LoadContext<'_>,
+  ) -> Result<Self::Asset, Self::Error> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).await?;
+    A::parse(&bytes)
+  }
+
+  fn extensions(&self) -> &[&str] {
+    A::EXTENSIONS
+  }
+}
+
+pub trait RegisterRonAsset {
+  fn register_ron_asset<A: RonAsset>(&mut self) -> &mut Self;
+}
+
+impl RegisterRonAsset for App {
+  fn register_ron_asset<A: RonAsset>(&mut self) -> &mut Self {
+    self
+      .init_asset::<A>()
+      .init_asset_loader::<RonAssetLoader<A>>()
+  }
+}